@@ -0,0 +1,101 @@
+//! Benchmarks building a stop's status from realtime feeds, the work done on every poll.
+//!
+//! By default the feed is generated to be about the size of the numbered lines' feed at
+//! rush hour. To benchmark a recorded feed instead, point `TRAIN_CHECKER_BENCH_FEED` at a
+//! saved protobuf response and `TRAIN_CHECKER_BENCH_STOP` at a stop it serves.
+
+use std::hint::black_box;
+use std::path::Path;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use prost::Message;
+use train_checker::TrainChecker;
+use train_checker::subway::trip_update::{StopTimeEvent, StopTimeUpdate};
+use train_checker::subway::{FeedEntity, FeedHeader, FeedMessage, TripDescriptor, TripUpdate};
+
+const NOW: i64 = 1_700_000_000;
+
+// Roughly the 1-7 feed mid-rush: a few hundred trips, each with its remaining stops
+const ROUTES: [&str; 7] = ["1", "2", "3", "4", "5", "6", "7"];
+const TRIPS_PER_ROUTE: usize = 40;
+const STOPS_PER_TRIP: usize = 40;
+
+/// A feed where every other trip passes through R16N partway along its run
+fn generated_feed() -> FeedMessage {
+    let mut entity = Vec::new();
+    for route_id in ROUTES {
+        for n in 0..TRIPS_PER_ROUTE {
+            let trip_id = format!("{:06}_{}..N03R", 60_000 + n * 300, route_id);
+            let stop_time_update = (0..STOPS_PER_TRIP)
+                .map(|i| {
+                    let stop_id = if n % 2 == 0 && i == STOPS_PER_TRIP / 2 {
+                        "R16N".to_string()
+                    } else {
+                        format!("{}{:02}N", route_id, i)
+                    };
+                    let time = NOW + (n * 120 + i * 90) as i64;
+                    StopTimeUpdate {
+                        stop_id: Some(stop_id),
+                        arrival: Some(StopTimeEvent {
+                            time: Some(time),
+                            ..Default::default()
+                        }),
+                        departure: Some(StopTimeEvent {
+                            time: Some(time + 30),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }
+                })
+                .collect();
+            entity.push(FeedEntity {
+                id: trip_id.clone(),
+                trip_update: Some(TripUpdate {
+                    trip: TripDescriptor {
+                        trip_id: Some(trip_id),
+                        route_id: Some(route_id.to_string()),
+                        ..Default::default()
+                    },
+                    stop_time_update,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+        }
+    }
+    FeedMessage {
+        header: FeedHeader {
+            gtfs_realtime_version: "2.0".to_string(),
+            ..Default::default()
+        },
+        entity,
+    }
+}
+
+fn process_feeds(c: &mut Criterion) {
+    let checker = TrainChecker::from_gtfs_path(
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/gtfs"),
+    )
+    .expect("fixture schedule loads");
+
+    let (feed, stop_id, now) = match std::env::var_os("TRAIN_CHECKER_BENCH_FEED") {
+        Some(path) => {
+            let bytes = std::fs::read(path).expect("recorded feed is readable");
+            let feed = FeedMessage::decode(bytes.as_slice()).expect("recorded feed decodes");
+            let stop_id =
+                std::env::var("TRAIN_CHECKER_BENCH_STOP").unwrap_or_else(|_| "R16N".to_string());
+            // Process as of when the feed was recorded, so its trains are still upcoming
+            let now = feed.header.timestamp.map_or(NOW, |at| at as i64);
+            (feed, stop_id, now)
+        }
+        None => (generated_feed(), "R16N".to_string(), NOW),
+    };
+    let feeds = [feed];
+
+    c.bench_function("process_feeds", |b| {
+        b.iter(|| checker.process_feeds(black_box(&stop_id), black_box(&feeds), now))
+    });
+}
+
+criterion_group!(benches, process_feeds);
+criterion_main!(benches);
@@ -0,0 +1,193 @@
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::Utc as ChronoUtc;
+use tracing::debug;
+
+use crate::{FeedOutcome, FetchSummary, StopStatus, TrainChecker, TrainCheckerConfig};
+
+// MTA Bus Time publishes a single GTFS-Realtime trip updates feed covering every bus in the
+// city. Unlike the subway feeds it requires a Bus Time API key, passed as a query parameter.
+const BUS_TRIP_UPDATES_URL: &str = "https://gtfsrt.prod.obanyc.com/tripUpdates";
+
+// Feed health is tracked under this name, alongside the subway feed suffixes
+const BUS_FEED_NAME: &str = "bus";
+
+/// The bus schedules the MTA publishes, one static GTFS zip per borough plus one for the
+/// MTA Bus Company's express and local routes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusBorough {
+    Bronx,
+    Brooklyn,
+    Manhattan,
+    Queens,
+    StatenIsland,
+    BusCompany,
+}
+
+impl BusBorough {
+    /// URL of the borough's static GTFS zip
+    pub fn gtfs_url(self) -> &'static str {
+        match self {
+            BusBorough::Bronx => "https://rrgtfsfeeds.s3.amazonaws.com/gtfs_bx.zip",
+            BusBorough::Brooklyn => "https://rrgtfsfeeds.s3.amazonaws.com/gtfs_b.zip",
+            BusBorough::Manhattan => "https://rrgtfsfeeds.s3.amazonaws.com/gtfs_m.zip",
+            BusBorough::Queens => "https://rrgtfsfeeds.s3.amazonaws.com/gtfs_q.zip",
+            BusBorough::StatenIsland => "https://rrgtfsfeeds.s3.amazonaws.com/gtfs_si.zip",
+            BusBorough::BusCompany => "https://rrgtfsfeeds.s3.amazonaws.com/gtfs_busco.zip",
+        }
+    }
+}
+
+/// Reports bus arrivals at a stop. Built on a `TrainChecker` loaded with a bus schedule, so
+/// stop lookups, arrival processing and request metrics all work the same as for the subway;
+/// only where the realtime data comes from differs.
+pub struct BusChecker {
+    checker: TrainChecker,
+    trip_updates_url: String, // includes the API key, so it's never logged
+}
+
+impl BusChecker {
+    /// Creates a BusChecker by downloading a borough's bus schedule
+    pub async fn new(borough: BusBorough, api_key: &str) -> Result<Self> {
+        let config = TrainCheckerConfig {
+            gtfs_url: borough.gtfs_url().to_string(),
+            ..TrainCheckerConfig::default()
+        };
+        let checker = TrainChecker::new_with_config(&config).await?;
+        Ok(Self::with_checker(checker, api_key))
+    }
+
+    /// Creates a BusChecker from a local bus GTFS zip or directory
+    pub fn from_gtfs_path(path: &Path, api_key: &str) -> Result<Self> {
+        Ok(Self::with_checker(
+            TrainChecker::from_gtfs_path(path)?,
+            api_key,
+        ))
+    }
+
+    fn with_checker(checker: TrainChecker, api_key: &str) -> Self {
+        Self {
+            checker,
+            trip_updates_url: format!("{}?key={}", BUS_TRIP_UPDATES_URL, api_key),
+        }
+    }
+
+    /// The underlying checker, for stop lookups and request metrics
+    pub fn checker(&self) -> &TrainChecker {
+        &self.checker
+    }
+
+    /// Gets the current status of a bus stop with upcoming bus arrivals
+    pub async fn get_stop_status(&self, stop_id: &str) -> Result<StopStatus> {
+        if !self.checker.is_valid_stop(stop_id) {
+            return Err(anyhow::anyhow!("Invalid stop ID: {}", stop_id));
+        }
+        if self.checker.offline {
+            return Err(anyhow::anyhow!("Bus arrivals aren't available offline"));
+        }
+
+        let now = ChronoUtc::now().timestamp();
+        let outcome = self
+            .checker
+            .fetch_feed(BUS_FEED_NAME, &self.trip_updates_url, None)
+            .await;
+        let mut summary = FetchSummary::default();
+        summary.count(&outcome);
+        self.checker.record_fetch_summary(summary);
+        let feed = match outcome {
+            FeedOutcome::Fetched(feed) => feed,
+            FeedOutcome::Failed(e) => return Err(e),
+            FeedOutcome::Throttled => {
+                return Err(anyhow::anyhow!(
+                    "Throttled: request rate limit reached, skipped the bus feed"
+                ));
+            }
+        };
+        debug!("Fetched bus trip updates: {} entities", feed.entity.len());
+
+        Ok(self.checker.process_feeds(stop_id, &[feed], now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subway::trip_update::{StopTimeEvent, StopTimeUpdate};
+    use crate::subway::{FeedEntity, FeedHeader, FeedMessage, TripDescriptor, TripUpdate};
+    use std::sync::Arc;
+
+    #[test]
+    fn bus_feeds_are_processed_like_subway_feeds() {
+        let mut gtfs = gtfs_structures::Gtfs::default();
+        let stop = gtfs_structures::Stop {
+            id: "401906".to_string(),
+            name: Some("1 Av/E 14 St".to_string()),
+            ..Default::default()
+        };
+        gtfs.stops.insert(stop.id.clone(), Arc::new(stop));
+        let bus = BusChecker::with_checker(TrainChecker::from_gtfs(gtfs), "secret");
+
+        let now = 1_700_000_000;
+        let feed = FeedMessage {
+            header: FeedHeader {
+                gtfs_realtime_version: "1.0".to_string(),
+                ..Default::default()
+            },
+            entity: vec![FeedEntity {
+                id: "MTA NYCT_OH_C5-Weekday-SDon-084000_M15_601".to_string(),
+                trip_update: Some(TripUpdate {
+                    trip: TripDescriptor {
+                        trip_id: Some("OH_C5-Weekday-SDon-084000_M15_601".to_string()),
+                        route_id: Some("M15".to_string()),
+                        ..Default::default()
+                    },
+                    stop_time_update: vec![StopTimeUpdate {
+                        stop_id: Some("401906".to_string()),
+                        arrival: Some(StopTimeEvent {
+                            time: Some(now + 240),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+        };
+
+        let status = bus.checker().process_feeds("401906", &[feed], now);
+
+        assert_eq!(status.stop_name.as_deref(), Some("1 Av/E 14 St"));
+        assert_eq!(status.direction, None);
+        assert_eq!(status.train_arrivals["M15"][0].arrival_time, 240);
+        assert!(bus.trip_updates_url.ends_with("?key=secret"));
+    }
+
+    #[tokio::test]
+    async fn failed_fetches_keep_the_api_key_out_of_errors_and_count_latency() {
+        let mut gtfs = gtfs_structures::Gtfs::default();
+        let stop = gtfs_structures::Stop {
+            id: "401906".to_string(),
+            ..Default::default()
+        };
+        gtfs.stops.insert(stop.id.clone(), Arc::new(stop));
+        // Nothing listens on a port just released, so the request is refused
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+        let bus = BusChecker {
+            checker: TrainChecker::from_gtfs(gtfs),
+            trip_updates_url: format!("http://{}/tripUpdates?key=secret", address),
+        };
+
+        let error = bus.get_stop_status("401906").await.unwrap_err();
+
+        assert!(!format!("{:#}", error).contains("secret"));
+        assert!(!format!("{:?}", error).contains("secret"));
+        let metrics = bus.checker().metrics();
+        assert_eq!(metrics.failed_requests, 1);
+        assert!(metrics.average_feed_latency.is_some());
+        assert_eq!(bus.checker().last_fetch_summary().unwrap().failed, 1);
+    }
+}
@@ -1,23 +1,55 @@
 use anyhow::{Context, Result};
-use chrono::{Duration as ChronoDuration, Utc as ChronoUtc};
+use chrono::{
+    DateTime, Duration as ChronoDuration, NaiveDate, NaiveTime, TimeZone, Utc as ChronoUtc,
+};
+use chrono_tz::Tz;
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
 use prost::Message;
-use std::collections::{HashMap, HashSet};
+use serde::Serialize;
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU32, Ordering};
-use std::time::Duration;
-use tracing::warn;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tracing::{debug, warn};
 
-mod subway;
-use subway::FeedMessage;
+mod bus;
+#[cfg(feature = "metrics-prometheus")]
+mod prometheus;
+pub mod subway;
+pub use bus::{BusBorough, BusChecker};
+use subway::trip_update::StopTimeUpdate;
+use subway::{FeedMessage, TripDescriptor};
 
 // This file represents the "normal" subway schedule and does not include most temporary service
 // changes, though some long term service changes may be included. It is typically updated a few times a year.
+// Default for TrainCheckerConfig::gtfs_url.
 const GTFS_URL: &str = "https://rrgtfsfeeds.s3.amazonaws.com/gtfs_subway.zip";
 
 // MTA GTFS-Realtime feed URLs. These endpoints require an API key.
-// The base URL is for the numbered lines (1, 2, 3, 4, 5, 6, 7)
+// The base URL is for the numbered lines (1, 2, 3, 4, 5, 6, 7). Default for
+// TrainCheckerConfig::feed_base_url.
 const MTA_SUBWAY_FEED_URL: &str =
     "https://api-endpoint.mta.info/Dataservice/mtagtfsfeeds/nyct%2Fgtfs";
 
+// The subway runs on New York time, so absolute arrival times are shown in this timezone.
+const MTA_TIMEZONE: Tz = chrono_tz::America::New_York;
+
+// Schedule download progress is reported each time this many more bytes have arrived
+const DOWNLOAD_PROGRESS_STEP: u64 = 256 * 1024;
+
+// A schedule download that receives nothing for this long is abandoned as stalled
+const DOWNLOAD_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+// A health probe that takes longer than this counts the feed as unreachable
+const HEALTHCHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+// A webhook that hasn't answered by then counts as a failed delivery
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
 // Suffixes for different routes in the MTA GTFS-Realtime feed.
 const SUFFIX_ACE: &str = "ace";
 const SUFFIX_BDFM: &str = "bdfm";
@@ -27,58 +59,767 @@ const SUFFIX_NQRW: &str = "nqrw";
 const SUFFIX_L: &str = "l";
 const SUFFIX_SIR: &str = "si";
 
+// Route IDs the Staten Island Railway has been published under. The static GTFS uses "SI"
+// (with short name "SIR"), but other MTA datasets label it differently.
+const SIR_ROUTE_IDS: &[&str] = &["SI", "SIR", "SS"];
+
+/// Strips the express suffix from a route ID, e.g. "6X" -> "6"
+fn base_route(route_id: &str) -> &str {
+    match route_id.strip_suffix('X') {
+        Some(base) if !base.is_empty() => base,
+        _ => route_id,
+    }
+}
+
+/// The realtime feed suffix carrying a route, or None for routes without a known feed.
+/// Express variants share their base route's feed, e.g. "6X" is in the numbered lines' feed.
+/// `is_sir_route` recognizes Staten Island Railway IDs beyond `SIR_ROUTE_IDS`.
+fn route_feed_suffix(route_id: &str, is_sir_route: impl Fn(&str) -> bool) -> Option<&'static str> {
+    match base_route(route_id) {
+        "A" | "C" | "E" => Some(SUFFIX_ACE),
+        "B" | "D" | "F" | "M" => Some(SUFFIX_BDFM),
+        "G" => Some(SUFFIX_G),
+        "J" | "Z" => Some(SUFFIX_JZ),
+        "N" | "Q" | "R" | "W" => Some(SUFFIX_NQRW),
+        "L" => Some(SUFFIX_L),
+        route if SIR_ROUTE_IDS.contains(&route) || is_sir_route(route) => Some(SUFFIX_SIR),
+        // These routes use the base URL without suffix
+        "1" | "2" | "3" | "4" | "5" | "6" | "7" => Some(""),
+        _ => None,
+    }
+}
+
+/// The feeds to fetch for a set of routes, each listed once in the order the routes first
+/// need it. Unknown routes are skipped so the remaining routes still get realtime data.
+fn feeds_for_routes<'a>(
+    routes: impl IntoIterator<Item = &'a str>,
+    is_sir_route: impl Fn(&str) -> bool,
+) -> Vec<&'static str> {
+    let mut feeds = Vec::new();
+    for route in routes {
+        match route_feed_suffix(route, &is_sir_route) {
+            Some(feed) if !feeds.contains(&feed) => feeds.push(feed),
+            Some(_) => {}
+            None => warn!("Skipping unknown route: {}", route),
+        }
+    }
+    feeds
+}
+
+/// A unique path in the temp directory for a schedule download in progress
+fn temp_download_path() -> PathBuf {
+    static NEXT_DOWNLOAD: AtomicU32 = AtomicU32::new(0);
+    std::env::temp_dir().join(format!(
+        "train-checker-gtfs-{}-{}.zip",
+        std::process::id(),
+        NEXT_DOWNLOAD.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Builds the URL of a realtime feed. The numbered lines use the base URL as-is and every
+/// other feed appends its suffix with a hyphen, e.g. "{base}-ace".
+fn feed_url(base_url: &str, feed_suffix: &str) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    if feed_suffix.is_empty() {
+        base_url.to_string()
+    } else {
+        format!("{}-{}", base_url, feed_suffix)
+    }
+}
+
+/// Reads when a trip left its first stop. Prefers the feed's start time ("HH:MM:SS", which
+/// can run past 24:00 for trips continuing after midnight), falling back to the origin time
+/// MTA trip IDs start with, in hundredths of a minute after midnight ("068700_1..N03R" is
+/// 11:27:00).
+fn parse_origin_time(start_time: Option<&str>, trip_id: Option<&str>) -> Option<NaiveTime> {
+    let from_start_time = start_time.and_then(|start_time| {
+        let mut parts = start_time
+            .splitn(3, ':')
+            .map(|part| part.parse::<u32>().ok());
+        let (hours, minutes, seconds) = (parts.next()??, parts.next()??, parts.next()??);
+        NaiveTime::from_hms_opt(hours % 24, minutes, seconds)
+    });
+    let from_trip_id = || {
+        let origin = trip_id?.split('_').next()?;
+        if origin.len() != 6 || !origin.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        // Hundredths of a minute -> seconds
+        let seconds = origin.parse::<u32>().ok()? * 60 / 100;
+        NaiveTime::from_num_seconds_from_midnight_opt(seconds % (24 * 60 * 60), 0)
+    };
+    from_start_time.or_else(from_trip_id)
+}
+
+/// Reduces an MTA trip ID to the part shared by the realtime and static feeds: the origin
+/// time, route and direction, e.g. "068700_1..N" for both "068700_1..N03R" (realtime) and
+/// "AFA24GEN-1038-Weekday-00_068700_1..N03R" (static). Returns None for IDs not in this format.
+fn trip_match_key(trip_id: &str) -> Option<&str> {
+    // The route is followed by one or two dots, then the direction letter
+    let dot = trip_id.find('.')?;
+    let route_start = trip_id[..dot].rfind('_')?;
+    let origin_start = trip_id[..route_start].rfind('_').map_or(0, |i| i + 1);
+    let after_dots = dot + trip_id[dot..].len() - trip_id[dot..].trim_start_matches('.').len();
+    let direction = trip_id[after_dots..].chars().next()?;
+    Some(&trip_id[origin_start..after_dots + direction.len_utf8()])
+}
+
 /// Represents a train arrival with route and timing information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct TrainArrival {
     pub route_id: String,
     pub route_name: Option<String>,
     pub arrival_time: i32, // seconds from now
     pub human_time: String,
+    pub arrival_local: DateTime<Tz>, // absolute arrival time in the MTA's local timezone
+    pub departure_time: Option<i32>, // seconds from now, when the feed has a departure time
+    pub departure_local: Option<DateTime<Tz>>,
+    pub trip_id: Option<String>,
+    pub is_express: bool, // e.g. a 6X, which shares route_name and feed with the 6
+    pub origin_time: Option<NaiveTime>, // when the trip left its first stop, e.g. "the 14:32 F"
+    pub headsign: Option<String>, // where the train is headed, from the static schedule's trip
+    pub approximate: bool, // estimated from the schedule and a delay, the feed gave no time
+}
+
+impl TrainArrival {
+    /// Seconds left until the train arrives as of `now`, negative once it's due.
+    /// Unlike `arrival_time` this stays correct between polls.
+    pub fn seconds_until(&self, now: DateTime<ChronoUtc>) -> i64 {
+        self.arrival_local.timestamp() - now.timestamp()
+    }
+
+    /// Like `seconds_until`, for the departure time if the feed provided one
+    pub fn departure_seconds_until(&self, now: DateTime<ChronoUtc>) -> Option<i64> {
+        self.departure_local
+            .map(|departure| departure.timestamp() - now.timestamp())
+    }
+}
+
+/// Direction of travel served by a platform stop, encoded in the stop ID's N/S suffix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum StopDirection {
+    North,
+    South,
+}
+
+impl StopDirection {
+    /// Parses the direction from a stop ID like "R16N", returning None for parent stations
+    pub fn from_stop_id(stop_id: &str) -> Option<Self> {
+        match stop_id.chars().last() {
+            Some('N') => Some(StopDirection::North),
+            Some('S') => Some(StopDirection::South),
+            _ => None,
+        }
+    }
+
+    /// Rider-facing label for the direction
+    pub fn label(&self) -> &'static str {
+        match self {
+            StopDirection::North => "Uptown",
+            StopDirection::South => "Downtown",
+        }
+    }
+}
+
+/// Wheelchair accessibility of a stop, from the GTFS `wheelchair_boarding` field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Accessibility {
+    Accessible,
+    NotAccessible,
+    Unknown,
+}
+
+impl From<&gtfs_structures::Availability> for Accessibility {
+    fn from(availability: &gtfs_structures::Availability) -> Self {
+        match availability {
+            gtfs_structures::Availability::Available => Accessibility::Accessible,
+            gtfs_structures::Availability::NotAvailable => Accessibility::NotAccessible,
+            _ => Accessibility::Unknown,
+        }
+    }
+}
+
+/// A transfer between two stops, from the GTFS `transfers.txt`
+#[derive(Debug, Clone, Serialize)]
+pub struct Transfer {
+    pub from_stop_id: String,
+    pub to_stop_id: String,
+    pub transfer_type: gtfs_structures::TransferType,
+    pub min_transfer_time: Option<u32>, // seconds
+}
+
+/// Where a train is relative to its current stop
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum VehicleStatus {
+    IncomingAt,
+    StoppedAt,
+    InTransitTo,
+}
+
+impl From<subway::vehicle_position::VehicleStopStatus> for VehicleStatus {
+    fn from(status: subway::vehicle_position::VehicleStopStatus) -> Self {
+        use subway::vehicle_position::VehicleStopStatus;
+        match status {
+            VehicleStopStatus::IncomingAt => VehicleStatus::IncomingAt,
+            VehicleStopStatus::StoppedAt => VehicleStatus::StoppedAt,
+            VehicleStopStatus::InTransitTo => VehicleStatus::InTransitTo,
+        }
+    }
+}
+
+/// Represents the physical progress of a train along its route
+#[derive(Debug, Clone, Serialize)]
+pub struct VehiclePosition {
+    pub route_id: String,
+    pub trip_id: Option<String>,
+    pub stop_id: Option<String>, // the stop the status refers to
+    pub stop_name: Option<String>,
+    pub status: VehicleStatus,
+    pub timestamp: Option<u64>, // unix time the position was measured
 }
 
 /// Represents the current state of a stop with upcoming trains
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct StopStatus {
     pub stop_id: String,
     pub stop_name: Option<String>,
+    pub direction: Option<StopDirection>,
     pub routes: HashSet<String>,
     pub train_arrivals: HashMap<String, Vec<TrainArrival>>, // route_id -> [TrainArrival]
+    pub simulated: bool,    // true when arrivals are fake data from offline mode
+    pub feeds_total: usize, // realtime feeds requested for the stop
+    pub feeds_succeeded: usize, // of those, how many responded, never none of them
+}
+
+/// Where a group of trains is going, as (route_id, headsign)
+pub type Destination<'a> = (&'a str, Option<&'a str>);
+
+impl StopStatus {
+    /// Arrivals grouped by route in a stable order (sorted by route_id), so displays
+    /// don't reshuffle between polls
+    pub fn sorted_routes(&self) -> Vec<(&str, &Vec<TrainArrival>)> {
+        let mut routes: Vec<(&str, &Vec<TrainArrival>)> = self
+            .train_arrivals
+            .iter()
+            .map(|(route_id, arrivals)| (route_id.as_str(), arrivals))
+            .collect();
+        routes.sort_by_key(|(route_id, _)| *route_id);
+        routes
+    }
+
+    /// Like `sorted_routes`, split further by destination so a route that runs to several
+    /// terminals, e.g. the 5 to Dyre Av or Nereid Av, gets a group per terminal. Groups are
+    /// keyed by (route_id, headsign); trains without a known headsign share a group.
+    pub fn arrivals_by_destination(&self) -> Vec<(Destination<'_>, Vec<&TrainArrival>)> {
+        let mut groups = Vec::new();
+        for (route_id, arrivals) in self.sorted_routes() {
+            let mut by_headsign: BTreeMap<Option<&str>, Vec<&TrainArrival>> = BTreeMap::new();
+            for arrival in arrivals {
+                by_headsign
+                    .entry(arrival.headsign.as_deref())
+                    .or_default()
+                    .push(arrival);
+            }
+            groups.extend(
+                by_headsign
+                    .into_iter()
+                    .map(|(headsign, arrivals)| ((route_id, headsign), arrivals)),
+            );
+        }
+        groups
+    }
+
+    /// Every upcoming arrival across routes, in no particular order
+    pub fn arrivals(&self) -> impl Iterator<Item = &TrainArrival> {
+        self.train_arrivals.values().flatten()
+    }
+
+    /// The `n` soonest arrivals across routes, soonest first. Keeps at most `n` arrivals
+    /// in a heap rather than sorting them all, so it's cheap when `n` is small.
+    pub fn soonest_arrivals(&self, n: usize) -> Vec<&TrainArrival> {
+        if n == 0 {
+            return Vec::new();
+        }
+        // A max-heap of the soonest seen so far, so the latest of them is evicted first
+        let mut heap = BinaryHeap::with_capacity(n + 1);
+        for arrival in self.arrivals() {
+            heap.push(ByArrivalTime(arrival));
+            if heap.len() > n {
+                heap.pop();
+            }
+        }
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|ByArrivalTime(arrival)| arrival)
+            .collect()
+    }
+
+    /// Seconds between consecutive arrivals on a route, soonest first. Empty when the
+    /// route has fewer than two upcoming trains.
+    pub fn headways(&self, route_id: &str) -> Vec<i32> {
+        let mut times: Vec<i32> = self
+            .train_arrivals
+            .get(route_id)
+            .map(|arrivals| arrivals.iter().map(|a| a.arrival_time).collect())
+            .unwrap_or_default();
+        times.sort();
+        times.windows(2).map(|pair| pair[1] - pair[0]).collect()
+    }
+
+    /// A terse one-line summary for status bars, e.g. "R16N  Q:2m,6m  R:4m", with the
+    /// soonest one or two trains per route in whole minutes
+    pub fn summary_line(&self) -> String {
+        let mut line = self.stop_id.clone();
+        for (route_id, arrivals) in self.sorted_routes() {
+            let minutes: Vec<String> = arrivals
+                .iter()
+                .take(2)
+                .map(|arrival| format!("{}m", arrival.arrival_time.max(0) / 60))
+                .collect();
+            line.push_str(&format!("  {}:{}", route_id, minutes.join(",")));
+        }
+        line
+    }
+
+    /// Whether some of the stop's realtime feeds didn't respond, so routes on them may be
+    /// missing from the arrivals. Only a partial outage shows up here: when no feed responds
+    /// the poll fails with an error instead of returning a status.
+    pub fn is_partial(&self) -> bool {
+        self.feeds_succeeded < self.feeds_total
+    }
+
+    /// Routes with upcoming trains in the realtime feed, sorted
+    pub fn live_routes(&self) -> Vec<&str> {
+        let mut routes: Vec<&str> = self.train_arrivals.keys().map(String::as_str).collect();
+        routes.sort();
+        routes
+    }
+
+    /// Routes that serve the stop in the static schedule but have no upcoming trains, e.g.
+    /// lines that don't run overnight. Sorted.
+    pub fn idle_routes(&self) -> Vec<&str> {
+        let mut routes: Vec<&str> = self
+            .routes
+            .iter()
+            .filter(|route_id| !self.train_arrivals.contains_key(*route_id))
+            .map(String::as_str)
+            .collect();
+        routes.sort();
+        routes
+    }
+}
+
+/// The arrivals at every platform of a station, e.g. both directions at Times Sq
+#[derive(Debug, Clone, Serialize)]
+pub struct StationStatus {
+    pub stop_id: String, // the parent station
+    pub stop_name: Option<String>,
+    pub platforms: Vec<StopStatus>, // one per platform, sorted by stop ID so North comes first
+}
+
+/// Orders arrivals by arrival time, then route and trip, so ties sort the same way
+/// every time
+struct ByArrivalTime<'a>(&'a TrainArrival);
+
+impl ByArrivalTime<'_> {
+    fn key(&self) -> (i32, &str, &Option<String>) {
+        (self.0.arrival_time, &self.0.route_id, &self.0.trip_id)
+    }
+}
+
+impl PartialEq for ByArrivalTime<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for ByArrivalTime<'_> {}
+
+impl PartialOrd for ByArrivalTime<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ByArrivalTime<'_> {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// Request health of a single realtime feed endpoint
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FeedHealth {
+    pub successes: u32,
+    pub failures: u32,
+    pub last_success: Option<DateTime<ChronoUtc>>,
+    pub consecutive_failures: u32, // failures since the last success
+    pub throttled: u32,            // requests skipped by the rate limit, not counted as failures
+}
+
+impl FeedHealth {
+    /// Whether the most recent request to the feed succeeded
+    pub fn is_healthy(&self) -> bool {
+        self.consecutive_failures == 0
+    }
+}
+
+/// Outcome of the feed requests made by a single poll. Unlike `Metrics`, which counts
+/// every request since startup, this shows the health of the current cycle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct FetchSummary {
+    pub requested: usize, // feeds the poll wanted
+    pub succeeded: usize,
+    pub failed: usize,
+    pub throttled: usize, // skipped by the rate limit, never requested
+}
+
+impl FetchSummary {
+    /// Whether every requested feed responded
+    pub fn is_complete(&self) -> bool {
+        self.succeeded == self.requested
+    }
+
+    /// Counts one requested feed's outcome
+    fn count(&mut self, outcome: &FeedOutcome) {
+        self.requested += 1;
+        match outcome {
+            FeedOutcome::Fetched(_) => self.succeeded += 1,
+            FeedOutcome::Failed(_) => self.failed += 1,
+            FeedOutcome::Throttled => self.throttled += 1,
+        }
+    }
+}
+
+/// What became of a single feed request, see `TrainChecker::fetch_feed`
+enum FeedOutcome {
+    Fetched(FeedMessage),
+    Failed(anyhow::Error),
+    Throttled, // skipped by the rate limit
+}
+
+/// How much of the static schedule zip has downloaded so far
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadProgress {
+    pub downloaded: u64,    // bytes
+    pub total: Option<u64>, // bytes, None when the server didn't say
+}
+
+impl DownloadProgress {
+    /// Percentage downloaded, or None when the total size is unknown
+    pub fn percent(&self) -> Option<u8> {
+        self.total
+            .filter(|&total| total > 0)
+            .map(|total| (self.downloaded.min(total) * 100 / total) as u8)
+    }
+}
+
+/// Result of an on-demand `TrainChecker::healthcheck`, suitable as a `/healthz` response body
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub healthy: bool, // the schedule is loaded and the feed answered, or we're offline
+    pub gtfs_loaded: bool, // stops, routes and trips were all populated
+    pub stop_count: usize,
+    pub feed_reachable: Option<bool>, // None when offline, since no probe is made
+    pub feed_latency: Option<Duration>,
+    pub feed_error: Option<String>, // why the probe failed, if it did
+    pub checked_at: DateTime<ChronoUtc>,
+}
+
+/// A route from the static schedule, with the colors used on its bullet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RouteInfo {
+    pub route_id: String,
+    pub short_name: Option<String>,
+    pub long_name: Option<String>,
+    pub color: (u8, u8, u8), // (r, g, b)
+    pub text_color: (u8, u8, u8),
+}
+
+/// Snapshot of request metrics, for monitoring a long-running checker
+#[derive(Debug, Clone, Serialize)]
+pub struct Metrics {
+    pub total_requests: u32,
+    pub failed_requests: u32,
+    pub throttled_requests: u32, // skipped by the rate limit, not included in total_requests
+    pub last_successful_poll: Option<DateTime<ChronoUtc>>,
+    pub average_feed_latency: Option<Duration>,
+}
+
+/// Describes how fresh the loaded static schedule is
+#[derive(Debug, Clone, Serialize)]
+pub struct GtfsInfo {
+    pub publisher: Option<String>,
+    pub version: Option<String>,
+    pub start_date: Option<NaiveDate>, // first day the schedule is valid, from feed_info.txt
+    pub end_date: Option<NaiveDate>,
+    pub downloaded_at: Option<DateTime<ChronoUtc>>,
+}
+
+impl GtfsInfo {
+    /// Short label for the schedule's age, e.g. "2024-05-01". Prefers the feed's own
+    /// start date or version and falls back to when the data was downloaded.
+    pub fn schedule_label(&self) -> Option<String> {
+        self.start_date
+            .map(|date| date.to_string())
+            .or_else(|| self.version.clone())
+            .or_else(|| {
+                self.downloaded_at
+                    .map(|at| at.with_timezone(&MTA_TIMEZONE).date_naive().to_string())
+            })
+    }
+}
+
+/// Running totals behind `Metrics`
+#[derive(Debug, Default)]
+struct RequestStats {
+    total_latency: Duration,
+    latency_samples: u32,
+    last_successful_poll: Option<DateTime<ChronoUtc>>,
+    #[cfg(feature = "metrics-prometheus")]
+    latency_histogram: prometheus::LatencyHistogram,
+}
+
+/// A train's upcoming stop at a platform, as read from the realtime feeds
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct RouteTime {
+    seconds: i32, // seconds until arrival, or departure for trains starting here
+    departure_seconds: Option<i32>,
+    trip_id: Option<String>,
+    start_time: Option<String>, // trip's start time from the feed, "HH:MM:SS"
+    approximate: bool,          // estimated from the schedule and a delay
 }
 
 /// Core train checker that manages GTFS data and realtime feeds
 pub struct TrainChecker {
     gtfs: gtfs_structures::Gtfs,
-    stop_name_to_id: HashMap<String, String>,
+    stop_name_to_id: HashMap<String, Vec<String>>, // names repeat across boroughs and platforms
     stop_id_to_name: HashMap<String, String>,
+    sorted_stop_ids: Vec<String>, // every stop ID, for stable listing and paging
+    trip_key_to_ids: HashMap<String, Vec<String>>, // trip_match_key -> static trip IDs, sorted
+    stop_routes: HashMap<String, HashSet<String>>, // stop ID -> routes with trips stopping there
     failed_requests: AtomicU32,
+    total_requests: AtomicU32,
+    feed_health: Mutex<HashMap<String, FeedHealth>>, // feed suffix -> FeedHealth
+    request_stats: Mutex<RequestStats>,
+    offline: bool, // when true, no network calls are made and arrivals are simulated
+    request_limit: Arc<Semaphore>, // caps concurrent realtime feed requests
+    rate_limit: Option<DefaultDirectRateLimiter>, // caps realtime feed requests per minute
+    throttled_requests: AtomicU32,
+    last_fetch: Mutex<Option<FetchSummary>>, // outcome of the latest poll, None before the first
+    gtfs_downloaded_at: Option<DateTime<ChronoUtc>>,
+    http_client: reqwest::Client, // shared so connections are reused between polls
+    max_horizon: Option<Duration>, // arrivals further out than this are left off the board
+    max_arrivals_per_route: usize,
+    feed_base_url: String, // realtime feeds are this URL plus a per-line suffix
+    api_key: Option<String>, // sent with each realtime feed request, if set
+    time_format: TimeFormat, // how TrainArrival::human_time is written
+    failure_threshold: u32, // failures in a row on a feed before get_status reports Error
 }
 
+/// Failures callers may want to tell apart from other errors, found by downcasting the
+/// `anyhow::Error` a TrainChecker method returned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrainCheckerError {
+    /// None of the routes serving the stop have a known realtime feed, so there's no live
+    /// data to poll for it. `routes` is sorted and empty if no scheduled trips stop there.
+    NoFeedsForStop {
+        stop_id: String,
+        routes: Vec<String>,
+    },
+}
+
+impl std::fmt::Display for TrainCheckerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrainCheckerError::NoFeedsForStop { stop_id, routes } if routes.is_empty() => {
+                write!(
+                    f,
+                    "No live data for stop {}: no scheduled routes serve it",
+                    stop_id
+                )
+            }
+            TrainCheckerError::NoFeedsForStop { stop_id, routes } => write!(
+                f,
+                "No live data for stop {}: no realtime feed carries its routes ({})",
+                stop_id,
+                routes.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TrainCheckerError {}
+
 pub enum TrainCheckerStatus {
     Ok,
     Error,
+    Throttled, // the latest poll skipped feeds to stay under the request rate limit
 }
 
 impl TrainChecker {
     /// Creates a new TrainChecker instance by fetching GTFS data
     pub async fn new() -> Result<Self> {
-        let gtfs = Self::fetch_gtfs_data().await?;
+        Self::new_with_config(&TrainCheckerConfig::default()).await
+    }
+
+    /// Creates a new TrainChecker instance by fetching GTFS data from the configured URL
+    pub async fn new_with_config(config: &TrainCheckerConfig) -> Result<Self> {
+        let gtfs = Self::fetch_gtfs_data(config, None, &|_| {}).await?;
+        let mut checker = Self::from_gtfs(gtfs).with_config(config);
+        checker.gtfs_downloaded_at = Some(ChronoUtc::now());
+        Ok(checker)
+    }
+
+    /// Creates a new TrainChecker by fetching GTFS data, saving a copy of the downloaded zip
+    /// to `cache_path` so later offline runs can load it
+    pub async fn new_with_cache(cache_path: &Path, config: &TrainCheckerConfig) -> Result<Self> {
+        Self::new_with_cache_and_progress(cache_path, config, &|_| {}).await
+    }
+
+    /// Like `new_with_cache`, calling `progress` as the schedule zip downloads so a slow
+    /// download visibly moves along
+    pub async fn new_with_cache_and_progress(
+        cache_path: &Path,
+        config: &TrainCheckerConfig,
+        progress: &(dyn Fn(DownloadProgress) + Sync),
+    ) -> Result<Self> {
+        let gtfs = Self::fetch_gtfs_data(config, Some(cache_path), progress).await?;
+        let mut checker = Self::from_gtfs(gtfs).with_config(config);
+        checker.gtfs_downloaded_at = Some(ChronoUtc::now());
+        Ok(checker)
+    }
+
+    /// Creates a TrainChecker that never touches the network. GTFS data is loaded from a
+    /// zip cached by `new_with_cache` and realtime arrivals are simulated.
+    pub fn new_offline(cache_path: &Path) -> Result<Self> {
+        let mut checker = Self::from_gtfs_path(cache_path)?;
+        checker.offline = true;
+        // The cache is written right after downloading, so its mtime is the download time
+        checker.gtfs_downloaded_at = std::fs::metadata(cache_path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .map(DateTime::<ChronoUtc>::from);
+        Ok(checker)
+    }
+
+    /// Creates a TrainChecker from a local GTFS zip or directory, without downloading the
+    /// schedule. Realtime feeds are still fetched from the network.
+    pub fn from_gtfs_path(path: &Path) -> Result<Self> {
+        let gtfs = gtfs_structures::Gtfs::from_path(path)
+            .with_context(|| format!("Failed to load GTFS data from {}", path.display()))?;
+        Self::validate_gtfs(&gtfs)?;
+        Ok(Self::from_gtfs(gtfs))
+    }
 
+    /// Creates a TrainChecker from already-loaded GTFS data
+    fn from_gtfs(gtfs: gtfs_structures::Gtfs) -> Self {
         // Build lookup maps for efficient stop name/ID lookups
-        let mut stop_name_to_id = HashMap::new();
+        let mut stop_name_to_id: HashMap<String, Vec<String>> = HashMap::new();
         let mut stop_id_to_name = HashMap::new();
         for (id, stop) in &gtfs.stops {
             if let Some(name) = &stop.name {
-                stop_name_to_id.insert(name.clone(), id.clone());
+                stop_name_to_id
+                    .entry(name.clone())
+                    .or_default()
+                    .push(id.clone());
                 stop_id_to_name.insert(id.clone(), name.clone());
             }
         }
+        for ids in stop_name_to_id.values_mut() {
+            ids.sort();
+        }
+        let mut sorted_stop_ids: Vec<String> = gtfs.stops.keys().cloned().collect();
+        sorted_stop_ids.sort();
+
+        let mut trip_key_to_ids: HashMap<String, Vec<String>> = HashMap::new();
+        for id in gtfs.trips.keys() {
+            if let Some(key) = trip_match_key(id) {
+                trip_key_to_ids
+                    .entry(key.to_string())
+                    .or_default()
+                    .push(id.clone());
+            }
+        }
+        for ids in trip_key_to_ids.values_mut() {
+            ids.sort();
+        }
+
+        let mut stop_routes: HashMap<String, HashSet<String>> = HashMap::new();
+        for trip in gtfs.trips.values() {
+            for stop_time in &trip.stop_times {
+                stop_routes
+                    .entry(stop_time.stop.id.clone())
+                    .or_default()
+                    .insert(trip.route_id.clone());
+            }
+        }
 
-        Ok(Self {
+        Self {
             gtfs,
             stop_name_to_id,
             stop_id_to_name,
+            sorted_stop_ids,
+            trip_key_to_ids,
+            stop_routes,
             failed_requests: AtomicU32::new(0),
-        })
+            total_requests: AtomicU32::new(0),
+            feed_health: Mutex::new(HashMap::new()),
+            request_stats: Mutex::new(RequestStats::default()),
+            offline: false,
+            request_limit: Arc::new(Semaphore::new(
+                TrainCheckerConfig::default().max_concurrent_requests,
+            )),
+            rate_limit: Self::build_rate_limit(
+                TrainCheckerConfig::default().max_requests_per_minute,
+            ),
+            throttled_requests: AtomicU32::new(0),
+            last_fetch: Mutex::new(None),
+            gtfs_downloaded_at: None,
+            http_client: Self::build_http_client(),
+            max_horizon: None,
+            max_arrivals_per_route: TrainCheckerConfig::default().max_arrivals_per_route,
+            feed_base_url: MTA_SUBWAY_FEED_URL.to_string(),
+            api_key: None,
+            time_format: TimeFormat::default(),
+            failure_threshold: TrainCheckerConfig::default().failure_threshold,
+        }
+    }
+
+    /// Applies a configuration to the checker
+    pub fn with_config(mut self, config: &TrainCheckerConfig) -> Self {
+        // A limit of zero would block every request forever
+        let limit = config.max_concurrent_requests.max(1);
+        self.request_limit = Arc::new(Semaphore::new(limit));
+        self.rate_limit = Self::build_rate_limit(config.max_requests_per_minute);
+        self.max_horizon = config.max_horizon;
+        self.max_arrivals_per_route = config.max_arrivals_per_route;
+        self.feed_base_url = config.feed_base_url.clone();
+        self.api_key = config.api_key.clone();
+        self.time_format = config.time_format;
+        // A threshold of zero would report Error before any request was made
+        self.failure_threshold = config.failure_threshold.max(1);
+        self
+    }
+
+    /// Builds the per-minute request limiter, or none when unlimited. The full minute's
+    /// allowance can be used in a burst, e.g. for several feeds at once.
+    fn build_rate_limit(max_requests_per_minute: Option<u32>) -> Option<DefaultDirectRateLimiter> {
+        max_requests_per_minute
+            .and_then(NonZeroU32::new)
+            .map(|limit| RateLimiter::direct(Quota::per_minute(limit)))
+    }
+
+    /// Returns version and freshness information about the loaded static schedule
+    pub fn gtfs_info(&self) -> GtfsInfo {
+        let feed_info = self.gtfs.feed_info.first();
+        GtfsInfo {
+            publisher: feed_info.map(|info| info.name.clone()),
+            version: feed_info.and_then(|info| info.version.clone()),
+            start_date: feed_info.and_then(|info| info.start_date),
+            end_date: feed_info.and_then(|info| info.end_date),
+            downloaded_at: self.gtfs_downloaded_at,
+        }
     }
 
     pub fn get_failed_requests_count(&self) -> u32 {
@@ -89,143 +830,627 @@ impl TrainChecker {
         self.failed_requests.store(0, Ordering::Relaxed);
     }
 
-    pub fn get_status(&self) -> TrainCheckerStatus {
-        if self.get_failed_requests_count() > 10 {
-            TrainCheckerStatus::Error
-        } else {
-            TrainCheckerStatus::Ok
-        }
+    /// Gets the request health of each feed queried so far, keyed by feed suffix.
+    /// The numbered lines (1-7) use the base feed URL and are keyed by an empty string.
+    pub fn get_feed_health(&self) -> HashMap<String, FeedHealth> {
+        self.feed_health.lock().unwrap().clone()
     }
 
-    /// Fetches the GTFS data. This is used to get the list of stops and routes.
-    async fn fetch_gtfs_data() -> Result<gtfs_structures::Gtfs> {
-        let gtfs = gtfs_structures::Gtfs::from_url_async(GTFS_URL)
-            .await
-            .context("Failed to fetch GTFS data from MTA feed")?;
-        Ok(gtfs)
+    /// Gets a snapshot of request counts, latency, and the last successful poll
+    pub fn metrics(&self) -> Metrics {
+        let stats = self.request_stats.lock().unwrap();
+        Metrics {
+            total_requests: self.total_requests.load(Ordering::Relaxed),
+            failed_requests: self.get_failed_requests_count(),
+            throttled_requests: self.throttled_requests.load(Ordering::Relaxed),
+            last_successful_poll: stats.last_successful_poll,
+            average_feed_latency: (stats.latency_samples > 0)
+                .then(|| stats.total_latency / stats.latency_samples),
+        }
     }
 
-    /// Gets all available stops with their names
-    pub fn get_all_stops(&self) -> Vec<(String, Option<String>)> {
-        let mut stops: Vec<_> = self.gtfs.stops.iter().collect();
-        stops.sort_by_key(|&(id, _)| id);
-        stops
-            .into_iter()
-            .map(|(id, stop)| (id.clone(), stop.name.clone()))
-            .collect()
+    /// Records how long a single feed request took
+    fn record_feed_latency(&self, latency: Duration) {
+        let mut stats = self.request_stats.lock().unwrap();
+        stats.total_latency += latency;
+        stats.latency_samples += 1;
+        #[cfg(feature = "metrics-prometheus")]
+        stats.latency_histogram.observe(latency);
     }
 
-    /// Validates if a stop ID exists
-    pub fn is_valid_stop(&self, stop_id: &str) -> bool {
-        self.gtfs.stops.contains_key(stop_id)
+    /// Records the outcome of a single feed request
+    fn record_feed_result(&self, feed_suffix: &str, success: bool) {
+        self.total_requests.fetch_add(1, Ordering::Relaxed);
+        let mut feed_health = self.feed_health.lock().unwrap();
+        let health = feed_health.entry(feed_suffix.to_string()).or_default();
+        if success {
+            health.successes += 1;
+            health.last_success = Some(ChronoUtc::now());
+            health.consecutive_failures = 0;
+        } else {
+            health.failures += 1;
+            health.consecutive_failures += 1;
+            self.failed_requests.fetch_add(1, Ordering::Relaxed);
+        }
     }
 
-    /// Gets the stop name for a given stop ID
-    pub fn get_stop_name(&self, stop_id: &str) -> Option<String> {
-        self.stop_id_to_name.get(stop_id).cloned()
+    /// Records a feed request skipped because the rate limit was reached
+    fn record_feed_throttled(&self, feed_suffix: &str) {
+        self.throttled_requests.fetch_add(1, Ordering::Relaxed);
+        let mut feed_health = self.feed_health.lock().unwrap();
+        feed_health
+            .entry(feed_suffix.to_string())
+            .or_default()
+            .throttled += 1;
     }
 
-    /// Gets the stop ID for a given stop name
-    pub fn get_stop_id(&self, stop_name: &str) -> Option<String> {
-        self.stop_name_to_id.get(stop_name).cloned()
+    /// Gets the outcome of the most recent poll's feed requests, or None before the first poll
+    pub fn last_fetch_summary(&self) -> Option<FetchSummary> {
+        *self.last_fetch.lock().unwrap()
     }
 
-    /// Gets all routes that serve a specific stop
-    pub fn get_routes_for_stop(&self, stop_id: &str) -> HashSet<String> {
-        let mut routes = HashSet::new();
+    fn record_fetch_summary(&self, summary: FetchSummary) {
+        *self.last_fetch.lock().unwrap() = Some(summary);
+    }
 
-        for trip in self.gtfs.trips.values() {
-            for stop_time in &trip.stop_times {
-                if stop_time.stop.id == stop_id {
-                    routes.insert(trip.route_id.clone());
-                    break;
-                }
-            }
+    /// Summarizes present health for display. Error means some feed has failed
+    /// `failure_threshold` times in a row, so a feed that recovers clears it, unlike the
+    /// lifetime count from `get_failed_requests_count`.
+    pub fn get_status(&self) -> TrainCheckerStatus {
+        let failing = self
+            .feed_health
+            .lock()
+            .unwrap()
+            .values()
+            .any(|health| health.consecutive_failures >= self.failure_threshold);
+        if failing {
+            TrainCheckerStatus::Error
+        } else if self
+            .last_fetch_summary()
+            .is_some_and(|summary| summary.throttled > 0)
+        {
+            TrainCheckerStatus::Throttled
+        } else {
+            TrainCheckerStatus::Ok
         }
-
-        routes
     }
 
-    /// Maps route IDs to their corresponding MTA realtime feed endpoints
-    fn get_realtime_feeds_for_routes(&self, routes: &HashSet<String>) -> Result<Vec<String>> {
-        let mut feeds = Vec::new();
-
-        // If a route ends is 'X', is it an express route.
-        // Strip the trailing 'X' from the route ID, if it exists, since it uses the same feed as the base route.
-        let routes: Vec<&str> = routes
-            .iter()
-            .map(|route| route.trim_end_matches('X'))
-            .collect();
-
-        for route in routes {
-            match route {
-                "A" | "C" | "E" => {
-                    if !feeds.contains(&SUFFIX_ACE.to_string()) {
-                        feeds.push(SUFFIX_ACE.to_string());
-                    }
-                }
-                "B" | "D" | "F" | "M" => {
-                    if !feeds.contains(&SUFFIX_BDFM.to_string()) {
-                        feeds.push(SUFFIX_BDFM.to_string());
-                    }
-                }
-                "G" => {
-                    if !feeds.contains(&SUFFIX_G.to_string()) {
-                        feeds.push(SUFFIX_G.to_string());
-                    }
-                }
-                "J" | "Z" => {
-                    if !feeds.contains(&SUFFIX_JZ.to_string()) {
-                        feeds.push(SUFFIX_JZ.to_string());
-                    }
-                }
-                "N" | "Q" | "R" | "W" => {
-                    if !feeds.contains(&SUFFIX_NQRW.to_string()) {
-                        feeds.push(SUFFIX_NQRW.to_string());
-                    }
-                }
-                "L" => {
-                    if !feeds.contains(&SUFFIX_L.to_string()) {
-                        feeds.push(SUFFIX_L.to_string());
-                    }
-                }
-                "SI" => {
-                    if !feeds.contains(&SUFFIX_SIR.to_string()) {
-                        feeds.push(SUFFIX_SIR.to_string());
-                    }
-                }
-                "1" | "2" | "3" | "4" | "5" | "6" | "7" => {
-                    // These routes use the base URL without suffix
-                    if !feeds.contains(&"".to_string()) {
-                        feeds.push("".to_string());
-                    }
-                }
-                _ => {
-                    // Unknown route, return error
-                    return Err(anyhow::anyhow!("Unknown route: {}", route));
-                }
+    /// Actively probes the checker's dependencies: whether the static schedule is loaded and
+    /// whether the realtime feed host answers a request. Unlike `get_status`, which reflects
+    /// failures accumulated by polling, this tests connectivity now. Only the response status
+    /// is read, and the probe isn't counted in metrics or against the rate limit, so it's
+    /// cheap enough to call on every health check.
+    pub async fn healthcheck(&self) -> HealthReport {
+        let gtfs_loaded = Self::validate_gtfs(&self.gtfs).is_ok();
+        let (feed_reachable, feed_latency, feed_error) = if self.offline {
+            (None, None, None)
+        } else {
+            let started = Instant::now();
+            match self.probe_feed().await {
+                Ok(()) => (Some(true), Some(started.elapsed()), None),
+                Err(e) => (Some(false), None, Some(format!("{:#}", e))),
             }
+        };
+        HealthReport {
+            healthy: gtfs_loaded && feed_reachable != Some(false),
+            gtfs_loaded,
+            stop_count: self.stop_count(),
+            feed_reachable,
+            feed_latency,
+            feed_error,
+            checked_at: ChronoUtc::now(),
         }
-
-        Ok(feeds)
     }
 
-    /// Fetches realtime data from a single MTA feed
-    async fn fetch_realtime_data(url: &str) -> Result<FeedMessage> {
-        let mut request = reqwest::Client::new().get(url);
-        request = request.header("Accept", "application/x-protobuf");
+    /// Requests the numbered lines' feed and checks the response status, without
+    /// downloading the body
+    async fn probe_feed(&self) -> Result<()> {
+        let mut request = self
+            .http_client
+            .get(feed_url(&self.feed_base_url, ""))
+            .timeout(HEALTHCHECK_TIMEOUT);
+        if let Some(api_key) = &self.api_key {
+            request = request.header("x-api-key", api_key);
+        }
         let response = request
             .send()
             .await
-            .context("Failed to fetch realtime data")?;
-
+            .context("Failed to reach realtime feed")?;
         if !response.status().is_success() {
             return Err(anyhow::anyhow!("HTTP error: {}", response.status()));
         }
+        Ok(())
+    }
 
-        let bytes = response
-            .bytes()
+    /// Fetches the GTFS data. This is used to get the list of stops and routes.
+    /// The zip is streamed into a temporary file, then copied to `cache_path` if set.
+    async fn fetch_gtfs_data(
+        config: &TrainCheckerConfig,
+        cache_path: Option<&Path>,
+        progress: &(dyn Fn(DownloadProgress) + Sync),
+    ) -> Result<gtfs_structures::Gtfs> {
+        let download_path = temp_download_path();
+        let result = Self::load_downloaded_gtfs(config, &download_path, cache_path, progress).await;
+        // Best effort, the temp directory is cleaned up eventually anyway
+        let _ = std::fs::remove_file(&download_path);
+        result
+    }
+
+    async fn load_downloaded_gtfs(
+        config: &TrainCheckerConfig,
+        download_path: &Path,
+        cache_path: Option<&Path>,
+        progress: &(dyn Fn(DownloadProgress) + Sync),
+    ) -> Result<gtfs_structures::Gtfs> {
+        Self::download_gtfs_zip(config, download_path, progress).await?;
+        let gtfs = gtfs_structures::Gtfs::from_path(download_path)
+            .context("Failed to parse GTFS data from MTA feed")?;
+        // Validated before caching so a corrupt download never replaces a good cache
+        Self::validate_gtfs(&gtfs)?;
+
+        // Failing to cache shouldn't prevent using the freshly downloaded data
+        if let Some(cache_path) = cache_path {
+            let cached = cache_path
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|_| std::fs::copy(download_path, cache_path).map(|_| ()));
+            if let Err(e) = cached {
+                warn!(
+                    "Failed to cache GTFS data at {}: {}",
+                    cache_path.display(),
+                    e
+                );
+            }
+        }
+        Ok(gtfs)
+    }
+
+    /// Checks that the tables the checker relies on were actually populated. A truncated
+    /// or malformed zip can parse without errors but leave them empty.
+    fn validate_gtfs(gtfs: &gtfs_structures::Gtfs) -> Result<()> {
+        let empty_tables: Vec<&str> = [
+            ("stops", gtfs.stops.is_empty()),
+            ("routes", gtfs.routes.is_empty()),
+            ("trips", gtfs.trips.is_empty()),
+        ]
+        .into_iter()
+        .filter_map(|(table, empty)| empty.then_some(table))
+        .collect();
+
+        if !empty_tables.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Downloaded schedule looks empty or corrupt (no {})",
+                empty_tables.join(", ")
+            ));
+        }
+        Ok(())
+    }
+
+    /// Streams the raw GTFS zip from the configured URL into `dest`, sending the auth header
+    /// if set. `progress` is called when the download starts, every
+    /// `DOWNLOAD_PROGRESS_STEP` bytes, and once it completes.
+    async fn download_gtfs_zip(
+        config: &TrainCheckerConfig,
+        dest: &Path,
+        progress: &(dyn Fn(DownloadProgress) + Sync),
+    ) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        // A stalled download would otherwise leave the loading screen up forever
+        let client = Self::http_client_builder()
+            .read_timeout(DOWNLOAD_READ_TIMEOUT)
+            .build()
+            .context("Failed to build HTTP client")?;
+        let mut request = client.get(&config.gtfs_url);
+        if let Some((name, value)) = &config.gtfs_auth_header {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        let mut response = request
+            .send()
+            .await
+            .context("Failed to fetch GTFS data from MTA feed")?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status()));
+        }
+
+        // Servers don't always send a length, and it's unknown for compressed responses
+        let mut status = DownloadProgress {
+            downloaded: 0,
+            total: response.content_length(),
+        };
+        progress(status);
+        let mut file = tokio::fs::File::create(dest)
+            .await
+            .with_context(|| format!("Failed to create {}", dest.display()))?;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .context("Failed to read GTFS data from MTA feed")?
+        {
+            file.write_all(&chunk)
+                .await
+                .with_context(|| format!("Failed to write {}", dest.display()))?;
+            let previous_step = status.downloaded / DOWNLOAD_PROGRESS_STEP;
+            status.downloaded += chunk.len() as u64;
+            if status.downloaded / DOWNLOAD_PROGRESS_STEP > previous_step {
+                progress(status);
+            }
+        }
+        file.flush().await?;
+        // The last update is always the finished size, even if a step was just reported
+        progress(status);
+        Ok(())
+    }
+
+    /// Gets all available stops with their names
+    pub fn get_all_stops(&self) -> Vec<(String, Option<String>)> {
+        self.get_stops_page(0, self.stop_count())
+    }
+
+    /// Gets up to `limit` stops starting at `offset`, in the same order as `get_all_stops`.
+    /// Empty once `offset` is past the last stop.
+    pub fn get_stops_page(&self, offset: usize, limit: usize) -> Vec<(String, Option<String>)> {
+        self.sorted_stop_ids
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .map(|id| (id.clone(), self.stop_id_to_name.get(id).cloned()))
+            .collect()
+    }
+
+    /// Number of stops in the schedule, for paging through `get_stops_page`
+    pub fn stop_count(&self) -> usize {
+        self.sorted_stop_ids.len()
+    }
+
+    /// Gets every route in the schedule, numbered lines first and then lettered ones,
+    /// with express variants right after their base route
+    pub fn get_all_routes(&self) -> Vec<RouteInfo> {
+        let mut routes: Vec<RouteInfo> = self
+            .gtfs
+            .routes
+            .values()
+            .map(|route| RouteInfo {
+                route_id: route.id.clone(),
+                short_name: route.short_name.clone(),
+                long_name: route.long_name.clone(),
+                color: (route.color.r, route.color.g, route.color.b),
+                text_color: (route.text_color.r, route.text_color.g, route.text_color.b),
+            })
+            .collect();
+
+        routes.sort_by_key(|route| {
+            let number = base_route(&route.route_id).parse::<u32>().ok();
+            (number.is_none(), number, route.route_id.clone())
+        });
+        routes
+    }
+
+    /// Gets the top-level stations (stops without a parent), collapsing the N/S platform
+    /// stops into their base station
+    pub fn get_parent_stations(&self) -> Vec<(String, Option<String>)> {
+        let mut stations: Vec<_> = self
+            .gtfs
+            .stops
+            .iter()
+            .filter(|(_, stop)| stop.parent_station.is_none())
+            .collect();
+        stations.sort_by_key(|&(id, _)| id);
+        stations
+            .into_iter()
+            .map(|(id, stop)| (id.clone(), stop.name.clone()))
+            .collect()
+    }
+
+    /// Validates if a stop ID exists
+    pub fn is_valid_stop(&self, stop_id: &str) -> bool {
+        self.gtfs.stops.contains_key(stop_id)
+    }
+
+    /// Gets the wheelchair accessibility of a stop. Platform stops without their own
+    /// information inherit it from their parent station.
+    pub fn stop_accessibility(&self, stop_id: &str) -> Accessibility {
+        let Some(stop) = self.gtfs.stops.get(stop_id) else {
+            return Accessibility::Unknown;
+        };
+
+        match Accessibility::from(&stop.wheelchair_boarding) {
+            Accessibility::Unknown => stop
+                .parent_station
+                .as_ref()
+                .and_then(|parent_id| self.gtfs.stops.get(parent_id))
+                .map(|parent| Accessibility::from(&parent.wheelchair_boarding))
+                .unwrap_or(Accessibility::Unknown),
+            accessibility => accessibility,
+        }
+    }
+
+    /// Gets the transfers available from a stop. MTA transfers are defined between parent
+    /// stations, so platform stops without their own transfers use their parent's.
+    pub fn get_transfers(&self, stop_id: &str) -> Vec<Transfer> {
+        let Some(stop) = self.gtfs.stops.get(stop_id) else {
+            return Vec::new();
+        };
+
+        let source = if stop.transfers.is_empty() {
+            stop.parent_station
+                .as_ref()
+                .and_then(|parent_id| self.gtfs.stops.get(parent_id))
+                .unwrap_or(stop)
+        } else {
+            stop
+        };
+
+        source
+            .transfers
+            .iter()
+            .map(|transfer| Transfer {
+                from_stop_id: source.id.clone(),
+                to_stop_id: transfer.to_stop_id.clone(),
+                transfer_type: transfer.transfer_type,
+                min_transfer_time: transfer.min_transfer_time,
+            })
+            .collect()
+    }
+
+    /// Gets the stop name for a given stop ID
+    pub fn get_stop_name(&self, stop_id: &str) -> Option<String> {
+        self.stop_id_to_name.get(stop_id).cloned()
+    }
+
+    /// Gets the IDs of every stop with the given name, sorted. Empty if none match.
+    pub fn get_stop_id(&self, stop_name: &str) -> Vec<String> {
+        self.stop_name_to_id
+            .get(stop_name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Finds the static trip for a realtime trip ID. Realtime IDs drop the static IDs' schedule
+    /// prefix and sometimes the trailing path code, so they are matched on origin time, route
+    /// and direction. When several service days share a trip, one with the exact path code is
+    /// preferred, then the first by ID.
+    pub fn resolve_trip(&self, realtime_trip_id: &str) -> Option<&gtfs_structures::Trip> {
+        if let Some(trip) = self.gtfs.trips.get(realtime_trip_id) {
+            return Some(trip);
+        }
+
+        let candidates = self
+            .trip_key_to_ids
+            .get(trip_match_key(realtime_trip_id)?)?;
+        let suffix = format!("_{}", realtime_trip_id);
+        let id = candidates
+            .iter()
+            .find(|id| id.ends_with(&suffix))
+            .or_else(|| candidates.first())?;
+        self.gtfs.trips.get(id)
+    }
+
+    /// Gets when the schedule has a trip reaching a stop, for comparing against the realtime
+    /// arrival when the feed gives no delay. The trip ID may be a realtime one, see
+    /// `resolve_trip`. Uses the departure time for stops the schedule only departs from.
+    pub fn scheduled_arrival(&self, stop_id: &str, trip_id: &str) -> Option<NaiveTime> {
+        let seconds = self.scheduled_seconds(stop_id, trip_id)?;
+        NaiveTime::from_num_seconds_from_midnight_opt(seconds % (24 * 60 * 60), 0)
+    }
+
+    /// Seconds after the service day's midnight the schedule has a trip reaching a stop.
+    /// Can run past 24:00 for trips continuing after midnight.
+    fn scheduled_seconds(&self, stop_id: &str, trip_id: &str) -> Option<u32> {
+        let stop_time = self
+            .resolve_trip(trip_id)?
+            .stop_times
+            .iter()
+            .find(|stop_time| stop_time.stop.id == stop_id)?;
+        stop_time.arrival_time.or(stop_time.departure_time)
+    }
+
+    /// Estimates when a train reaches a stop from its scheduled time plus the delay the feed
+    /// reports, for stop updates that have a delay but no absolute time. The service day is
+    /// the trip's start date, or today in New York if the feed doesn't say.
+    fn estimate_from_delay(
+        &self,
+        stop_id: &str,
+        trip: &TripDescriptor,
+        stop_update: &StopTimeUpdate,
+        now: i64,
+    ) -> Option<i64> {
+        let delay = stop_update
+            .arrival
+            .as_ref()
+            .and_then(|arrival| arrival.delay)
+            .or_else(|| stop_update.departure.as_ref()?.delay)?;
+        let scheduled = self.scheduled_seconds(stop_id, trip.trip_id.as_deref()?)?;
+        let service_date = match trip.start_date.as_deref() {
+            Some(start_date) => NaiveDate::parse_from_str(start_date, "%Y%m%d").ok()?,
+            None => DateTime::from_timestamp(now, 0)?
+                .with_timezone(&MTA_TIMEZONE)
+                .date_naive(),
+        };
+        let midnight = MTA_TIMEZONE
+            .from_local_datetime(&service_date.and_time(NaiveTime::MIN))
+            .earliest()?;
+        Some(midnight.timestamp() + scheduled as i64 + delay as i64)
+    }
+
+    /// Gets all routes that serve a specific stop
+    pub fn get_routes_for_stop(&self, stop_id: &str) -> HashSet<String> {
+        self.stop_routes.get(stop_id).cloned().unwrap_or_default()
+    }
+
+    /// Gets the IDs of every stop a route serves, in the order the route's longest trip
+    /// visits their stations. Both platforms of a station sit together; stops the longest
+    /// trip skips come last, sorted by ID.
+    pub fn get_stops_for_route(&self, route_id: &str) -> Vec<String> {
+        let trips: Vec<&gtfs_structures::Trip> = self
+            .gtfs
+            .trips
+            .values()
+            .filter(|trip| trip.route_id == route_id)
+            .collect();
+
+        // Platforms are ordered by their parent station so both directions line up
+        let station = |stop: &gtfs_structures::Stop| {
+            stop.parent_station
+                .clone()
+                .unwrap_or_else(|| stop.id.clone())
+        };
+        let longest = trips.iter().max_by(|a, b| {
+            a.stop_times
+                .len()
+                .cmp(&b.stop_times.len())
+                .then_with(|| b.id.cmp(&a.id))
+        });
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        for (position, stop_time) in longest
+            .iter()
+            .flat_map(|trip| trip.stop_times.iter())
+            .enumerate()
+        {
+            positions
+                .entry(station(&stop_time.stop))
+                .or_insert(position);
+        }
+
+        let mut stops: Vec<&gtfs_structures::Stop> = Vec::new();
+        let mut seen = HashSet::new();
+        for stop_time in trips.iter().flat_map(|trip| trip.stop_times.iter()) {
+            if seen.insert(stop_time.stop.id.as_str()) {
+                stops.push(&stop_time.stop);
+            }
+        }
+        stops.sort_by(|a, b| {
+            let position = |stop| positions.get(&station(stop)).copied().unwrap_or(usize::MAX);
+            position(a).cmp(&position(b)).then_with(|| a.id.cmp(&b.id))
+        });
+        stops.into_iter().map(|stop| stop.id.clone()).collect()
+    }
+
+    /// Whether a route is the Staten Island Railway, either by a known route ID or by how
+    /// the static schedule names it
+    fn is_sir_route(&self, route_id: &str) -> bool {
+        SIR_ROUTE_IDS.contains(&route_id)
+            || self.gtfs.routes.get(route_id).is_some_and(|route| {
+                route.short_name.as_deref() == Some("SIR")
+                    || route
+                        .long_name
+                        .as_deref()
+                        .is_some_and(|name| name.contains("Staten Island"))
+            })
+    }
+
+    /// Maps route IDs to their corresponding MTA realtime feed endpoints
+    fn get_realtime_feeds_for_routes(&self, routes: &HashSet<String>) -> Result<Vec<String>> {
+        let feeds = feeds_for_routes(routes.iter().map(String::as_str), |route| {
+            self.is_sir_route(route)
+        });
+
+        if feeds.is_empty() {
+            let routes: Vec<&str> = routes.iter().map(|route| base_route(route)).collect();
+            return Err(anyhow::anyhow!(
+                "No realtime feeds found for routes: {:?}",
+                routes
+            ));
+        }
+
+        Ok(feeds.into_iter().map(str::to_string).collect())
+    }
+
+    /// Lists the suffixes of the realtime feeds `get_stop_status` would fetch for a stop,
+    /// sorted, without fetching them. The numbered lines' feed is the empty string. Empty for
+    /// unknown stops and stops without a known feed.
+    pub fn feeds_for_stop(&self, stop_id: &str) -> Vec<String> {
+        let routes = self.get_routes_for_stop(stop_id);
+        let mut feeds: Vec<String> = feeds_for_routes(routes.iter().map(String::as_str), |route| {
+            self.is_sir_route(route)
+        })
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+        feeds.sort();
+        feeds
+    }
+
+    /// Like `get_realtime_feeds_for_routes` for the routes serving a stop, failing with
+    /// `TrainCheckerError::NoFeedsForStop` when none of them have a feed
+    fn realtime_feeds_for_stop(
+        &self,
+        stop_id: &str,
+        routes: &HashSet<String>,
+    ) -> Result<Vec<String>> {
+        self.get_realtime_feeds_for_routes(routes).map_err(|_| {
+            let mut routes: Vec<String> = routes.iter().cloned().collect();
+            routes.sort();
+            TrainCheckerError::NoFeedsForStop {
+                stop_id: stop_id.to_string(),
+                routes,
+            }
+            .into()
+        })
+    }
+
+    /// Builds the HTTP client used for realtime requests. Compressed responses are
+    /// decompressed transparently.
+    fn build_http_client() -> reqwest::Client {
+        Self::http_client_builder()
+            .build()
+            .expect("Failed to build HTTP client")
+    }
+
+    /// Settings shared by every HTTP client the checker makes
+    fn http_client_builder() -> reqwest::ClientBuilder {
+        reqwest::Client::builder().gzip(true).deflate(true)
+    }
+
+    /// Returns a future that opens a connection to the realtime feed host on the shared
+    /// client, so the first poll doesn't pay for DNS and TLS setup. It owns everything it
+    /// needs, so it can be spawned while the user is still picking a stop.
+    pub fn warm_up(&self) -> impl Future<Output = ()> + Send + 'static {
+        let client = self.http_client.clone();
+        let offline = self.offline;
+        let url = feed_url(&self.feed_base_url, "");
+        async move {
+            if offline {
+                return;
+            }
+            // Only the connection matters, so the cheap HEAD response is discarded
+            match client.head(url).send().await {
+                Ok(response) => debug!("Warmed up feed connection: {}", response.status()),
+                Err(e) => debug!("Failed to warm up feed connection: {}", e),
+            }
+        }
+    }
+
+    /// Fetches realtime data from a single MTA feed, passing the API key in the
+    /// `x-api-key` header if there is one
+    async fn fetch_realtime_data(
+        client: &reqwest::Client,
+        url: &str,
+        api_key: Option<&str>,
+    ) -> Result<FeedMessage> {
+        let mut request = client.get(url);
+        request = request
+            .header("Accept", "application/x-protobuf")
+            .header("Accept-Encoding", "gzip, deflate");
+        if let Some(api_key) = api_key {
+            request = request.header("x-api-key", api_key);
+        }
+        // The URL may carry an API key, e.g. the Bus Time feed's, so it's left out of errors
+        let response = request
+            .send()
+            .await
+            .map_err(reqwest::Error::without_url)
+            .context("Failed to fetch realtime data")?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("HTTP error: {}", response.status()));
+        }
+
+        let bytes = response
+            .bytes()
             .await
+            .map_err(reqwest::Error::without_url)
             .context("Failed to read realtime response bytes")?;
 
         let feed_message = FeedMessage::decode(bytes.as_ref())
@@ -234,152 +1459,616 @@ impl TrainChecker {
         Ok(feed_message)
     }
 
-    /// Fetches and combines realtime data from multiple MTA feeds
-    async fn fetch_combined_realtime_data(&self, feeds: &[String]) -> Result<Vec<FeedMessage>> {
+    /// Fetches a single realtime feed, recording its latency and outcome under `feed_name`.
+    /// Waits for a `max_concurrent_requests` slot, and skips the request when over the
+    /// per-minute rate limit.
+    async fn fetch_feed(&self, feed_name: &str, url: &str, api_key: Option<&str>) -> FeedOutcome {
+        if let Some(rate_limit) = &self.rate_limit
+            && rate_limit.check().is_err()
+        {
+            debug!("Rate limit reached, skipping feed '{}'", feed_name);
+            self.record_feed_throttled(feed_name);
+            return FeedOutcome::Throttled;
+        }
+
+        // The semaphore is never closed, so acquiring only fails if that changes
+        let _permit = self
+            .request_limit
+            .acquire()
+            .await
+            .expect("request limit semaphore closed");
+        let started = Instant::now();
+        let result = Self::fetch_realtime_data(&self.http_client, url, api_key).await;
+        self.record_feed_latency(started.elapsed());
+        self.record_feed_result(feed_name, result.is_ok());
+        match result {
+            Ok(feed) => {
+                self.request_stats.lock().unwrap().last_successful_poll = Some(ChronoUtc::now());
+                FeedOutcome::Fetched(feed)
+            }
+            Err(e) => {
+                warn!("Failed to fetch feed '{}': {}", feed_name, e);
+                FeedOutcome::Failed(e)
+            }
+        }
+    }
+
+    /// Fetches and combines realtime data from multiple MTA feeds, along with a summary of
+    /// how this call's requests went. The summary is also kept for `last_fetch_summary`,
+    /// including when every feed failed and an error is returned.
+    async fn fetch_combined_realtime_data(
+        &self,
+        feeds: &[String],
+    ) -> Result<(Vec<FeedMessage>, FetchSummary)> {
         if feeds.is_empty() {
             return Err(anyhow::anyhow!("No feeds provided for realtime data"));
         }
 
-        // Make parallel requests to the feeds.
-        let mut handles = Vec::new();
-        for feed_suffix in feeds {
-            let url = if feed_suffix.is_empty() {
-                MTA_SUBWAY_FEED_URL.to_string()
-            } else {
-                // The MTA feed url appends the feed suffix to the base url with a hyphen.
-                format!("{}-{}", MTA_SUBWAY_FEED_URL, feed_suffix)
-            };
-
-            let handle = tokio::spawn(async move { Self::fetch_realtime_data(&url).await });
-            handles.push(handle);
+        if self.offline {
+            return Ok((Vec::new(), FetchSummary::default()));
         }
 
+        // Request the feeds concurrently, at most `max_concurrent_requests` at a time
+        let outcomes = futures::future::join_all(feeds.iter().map(|feed_suffix| async move {
+            let url = feed_url(&self.feed_base_url, feed_suffix);
+            self.fetch_feed(feed_suffix, &url, self.api_key.as_deref())
+                .await
+        }))
+        .await;
+
+        let mut summary = FetchSummary::default();
         let mut feed_messages = Vec::new();
-        for handle in handles {
-            match handle.await {
-                Ok(Ok(feed)) => {
-                    feed_messages.push(feed);
-                }
-                Ok(Err(e)) => {
-                    warn!("Failed to fetch feed: {}", e);
-                    self.failed_requests.fetch_add(1, Ordering::Relaxed);
-                }
-                Err(e) => {
-                    warn!("Task failed: {}", e);
-                    self.failed_requests.fetch_add(1, Ordering::Relaxed);
-                }
+        for outcome in outcomes {
+            summary.count(&outcome);
+            if let FeedOutcome::Fetched(feed) = outcome {
+                feed_messages.push(feed);
             }
         }
+        self.record_fetch_summary(summary);
+
+        // With every feed down there's nothing to show, which shouldn't look like an empty board
+        if feed_messages.is_empty() && summary.throttled > 0 {
+            return Err(anyhow::anyhow!(
+                "Throttled: request rate limit reached, skipped {:?}",
+                feeds
+            ));
+        }
+        if feed_messages.is_empty() {
+            return Err(anyhow::anyhow!("All realtime feeds failed: {:?}", feeds));
+        }
 
-        Ok(feed_messages)
+        Ok((feed_messages, summary))
     }
 
     /// Gets the current status of a stop with upcoming train arrivals
     pub async fn get_stop_status(&self, stop_id: &str) -> Result<StopStatus> {
+        self.get_stop_status_with_lead(stop_id, 0).await
+    }
+
+    /// Fetches a stop's arrivals as a one-line summary, see `StopStatus::summary_line`
+    pub async fn summary_line(&self, stop_id: &str) -> Result<String> {
+        Ok(self.get_stop_status(stop_id).await?.summary_line())
+    }
+
+    /// Gets the current status of a stop, leaving out trains arriving in less than
+    /// `min_lead_seconds` (e.g. the walk time to the platform) since they can't be caught
+    pub async fn get_stop_status_with_lead(
+        &self,
+        stop_id: &str,
+        min_lead_seconds: i32,
+    ) -> Result<StopStatus> {
+        self.fetch_stop_status(stop_id, ChronoUtc::now().timestamp(), min_lead_seconds)
+            .await
+    }
+
+    /// Gets the current status of a stop with arrival times computed relative to the `now`
+    /// unix timestamp instead of the wall clock, so results can be reproduced
+    pub async fn get_stop_status_at(&self, stop_id: &str, now: i64) -> Result<StopStatus> {
+        self.fetch_stop_status(stop_id, now, 0).await
+    }
+
+    /// Fetches the realtime feeds for a stop and builds its status as of `now`
+    async fn fetch_stop_status(
+        &self,
+        stop_id: &str,
+        now: i64,
+        min_lead_seconds: i32,
+    ) -> Result<StopStatus> {
         if !self.is_valid_stop(stop_id) {
             return Err(anyhow::anyhow!("Invalid stop ID: {}", stop_id));
         }
 
         let routes = self.get_routes_for_stop(stop_id);
-        let feeds = self.get_realtime_feeds_for_routes(&routes)?;
-        let realtime_feeds = self.fetch_combined_realtime_data(&feeds).await?;
 
-        // Map of route ID to a list of arrival times.
-        let mut route_times: HashMap<String, Vec<i32>> = HashMap::new();
-        let current_timestamp = ChronoUtc::now().timestamp();
+        if self.offline {
+            let feed = Self::simulated_feed(stop_id, &routes, now);
+            let mut status =
+                self.build_stop_status(stop_id, routes, &[feed], now, min_lead_seconds);
+            status.simulated = true;
+            // Nothing was requested, so nothing could fail
+            status.feeds_total = 0;
+            status.feeds_succeeded = 0;
+            return Ok(status);
+        }
 
-        // Process realtime data to find upcoming trains
-        for feed in &realtime_feeds {
-            for entity in &feed.entity {
-                if let Some(trip_update) = &entity.trip_update {
-                    for stop_update in &trip_update.stop_time_update {
-                        if let Some(stop_id_update) = &stop_update.stop_id {
-                            if stop_id_update == stop_id {
-                                // Found a train coming to our stop
-                                if let Some(arrival) = &stop_update.arrival {
-                                    if let Some(arrival_time) = arrival.time {
-                                        let time_diff =
-                                            arrival_time as i32 - current_timestamp as i32;
-                                        if time_diff > 0 {
-                                            // Get route ID from trip descriptor
-                                            if let Some(route_id) = &trip_update.trip.route_id {
-                                                route_times
-                                                    .entry(route_id.clone())
-                                                    .or_insert_with(Vec::new)
-                                                    .push(time_diff);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+        let feeds = self.realtime_feeds_for_stop(stop_id, &routes)?;
+        let (realtime_feeds, summary) = self.fetch_combined_realtime_data(&feeds).await?;
+
+        let mut status =
+            self.build_stop_status(stop_id, routes, &realtime_feeds, now, min_lead_seconds);
+        status.feeds_total = summary.requested;
+        status.feeds_succeeded = summary.succeeded;
+        Ok(status)
+    }
+
+    /// Builds the status of a stop from already-fetched realtime feeds, as of the
+    /// `now` unix timestamp. Does no network access, so it can be used with recorded feeds.
+    pub fn process_feeds(&self, stop_id: &str, feeds: &[FeedMessage], now: i64) -> StopStatus {
+        self.process_feeds_with_lead(stop_id, feeds, now, 0)
+    }
+
+    /// Like `process_feeds`, leaving out trains arriving in less than `min_lead_seconds`
+    pub fn process_feeds_with_lead(
+        &self,
+        stop_id: &str,
+        feeds: &[FeedMessage],
+        now: i64,
+        min_lead_seconds: i32,
+    ) -> StopStatus {
+        let routes = self.get_routes_for_stop(stop_id);
+        self.build_stop_status(stop_id, routes, feeds, now, min_lead_seconds)
+    }
+
+    /// Drops times further out than `max_horizon`, if one is set
+    fn drop_past_horizon(&self, times: &mut Vec<RouteTime>) {
+        if let Some(max_horizon) = self.max_horizon {
+            times.retain(|time| time.seconds as u64 <= max_horizon.as_secs());
         }
+    }
+
+    fn build_stop_status(
+        &self,
+        stop_id: &str,
+        routes: HashSet<String>,
+        realtime_feeds: &[FeedMessage],
+        now: i64,
+        min_lead_seconds: i32,
+    ) -> StopStatus {
+        let route_times = self.collect_route_times(stop_id, realtime_feeds, now, min_lead_seconds);
 
         // Convert to TrainArrival objects
         // Map of route ID to a list of TrainArrival objects.
         let mut train_arrivals: HashMap<String, Vec<TrainArrival>> = HashMap::new();
-        for (route_id, times) in route_times {
-            let mut sorted_times = times.clone();
-            sorted_times.sort();
-            let next_trains = sorted_times.iter().take(2);
-
-            let route_name = self
-                .gtfs
-                .routes
-                .get(&route_id)
-                .and_then(|r| r.short_name.clone());
-            let arrivals: Vec<TrainArrival> = next_trains
-                .map(|&seconds| {
-                    let future_time = ChronoUtc::now() + ChronoDuration::seconds(seconds as i64);
-                    let human_time = chrono_humanize::HumanTime::from(future_time).to_string();
-                    TrainArrival {
-                        route_id: route_id.clone(),
-                        route_name: route_name.clone(),
-                        arrival_time: seconds,
-                        human_time,
-                    }
-                })
+        for (route_id, mut times) in route_times {
+            self.drop_past_horizon(&mut times);
+            if times.is_empty() {
+                continue;
+            }
+            times.sort();
+            let arrivals: Vec<TrainArrival> = times
+                .into_iter()
+                .take(self.max_arrivals_per_route)
+                .map(|time| self.build_arrival(&route_id, time, now))
                 .collect();
 
             train_arrivals.insert(route_id, arrivals);
         }
 
-        Ok(StopStatus {
+        StopStatus {
             stop_id: stop_id.to_string(),
             stop_name: self.get_stop_name(stop_id),
+            direction: StopDirection::from_stop_id(stop_id),
             routes,
             train_arrivals,
+            simulated: false,
+            feeds_total: realtime_feeds.len(),
+            feeds_succeeded: realtime_feeds.len(),
+        }
+    }
+
+    /// Builds a deterministic fake feed for offline mode, with two upcoming trains
+    /// for each route serving the stop
+    fn simulated_feed(stop_id: &str, routes: &HashSet<String>, now: i64) -> FeedMessage {
+        use subway::trip_update::{StopTimeEvent, StopTimeUpdate};
+        use subway::{FeedEntity, FeedHeader, TripDescriptor, TripUpdate};
+
+        let mut routes: Vec<&String> = routes.iter().collect();
+        routes.sort();
+
+        let entity = routes
+            .into_iter()
+            .enumerate()
+            .flat_map(|(i, route_id)| {
+                (0..2).map(move |n| {
+                    // Stagger routes so the board has a plausible spread of arrivals
+                    let seconds = 120 + i as i64 * 90 + n * 480;
+                    let trip_id = format!("simulated_{}_{}", route_id, n);
+                    FeedEntity {
+                        id: trip_id.clone(),
+                        trip_update: Some(TripUpdate {
+                            trip: TripDescriptor {
+                                trip_id: Some(trip_id),
+                                route_id: Some(route_id.clone()),
+                                ..Default::default()
+                            },
+                            stop_time_update: vec![StopTimeUpdate {
+                                stop_id: Some(stop_id.to_string()),
+                                arrival: Some(StopTimeEvent {
+                                    time: Some(now + seconds),
+                                    ..Default::default()
+                                }),
+                                departure: Some(StopTimeEvent {
+                                    time: Some(now + seconds + 30),
+                                    ..Default::default()
+                                }),
+                                ..Default::default()
+                            }],
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }
+                })
+            })
+            .collect();
+
+        FeedMessage {
+            header: FeedHeader {
+                gtfs_realtime_version: "2.0".to_string(),
+                timestamp: Some(now as u64),
+                ..Default::default()
+            },
+            entity,
+        }
+    }
+
+    /// Finds upcoming trains at a stop in already-fetched realtime feeds, skipping trains
+    /// arriving sooner than `min_lead_seconds`.
+    /// Returns a map of route ID to a list of (seconds from now, trip ID).
+    fn collect_route_times(
+        &self,
+        stop_id: &str,
+        realtime_feeds: &[FeedMessage],
+        current_timestamp: i64,
+        min_lead_seconds: i32,
+    ) -> HashMap<String, Vec<RouteTime>> {
+        let mut route_times: HashMap<String, Vec<RouteTime>> = HashMap::new();
+        // Trip IDs already counted, in case a trip appears in more than one feed
+        let mut seen: HashSet<&str> = HashSet::new();
+
+        // Process realtime data to find upcoming trains. This runs over every update in
+        // every feed on each poll, so it's kept lean; see benches/process_feeds.rs.
+        for feed in realtime_feeds {
+            for entity in &feed.entity {
+                let Some(trip_update) = &entity.trip_update else {
+                    continue;
+                };
+                let trip = &trip_update.trip;
+                for stop_update in &trip_update.stop_time_update {
+                    // Checked before anything else, since nearly every update is for another stop
+                    if stop_update.stop_id.as_deref() != Some(stop_id) {
+                        continue;
+                    }
+                    // Found a train coming to our stop
+                    let arrival_time = stop_update.arrival.as_ref().and_then(|a| a.time);
+                    let departure_time = stop_update.departure.as_ref().and_then(|d| d.time);
+                    // Trains starting their trip here only have a departure time. Updates
+                    // with only a delay are placed relative to the schedule instead.
+                    let (time, approximate) = match arrival_time.or(departure_time) {
+                        Some(time) => (time, false),
+                        None => match self.estimate_from_delay(
+                            stop_id,
+                            trip,
+                            stop_update,
+                            current_timestamp,
+                        ) {
+                            Some(time) => (time, true),
+                            None => continue,
+                        },
+                    };
+                    let time_diff = time as i32 - current_timestamp as i32;
+                    if time_diff <= 0 || time_diff < min_lead_seconds {
+                        continue;
+                    }
+                    // Once a trip is counted here any later updates for this stop are
+                    // duplicates, so the rest of the trip's updates can be skipped
+                    if let Some(trip_id) = trip.trip_id.as_deref()
+                        && !seen.insert(trip_id)
+                    {
+                        break;
+                    }
+
+                    // Get route ID from trip descriptor
+                    if let Some(route_id) = &trip.route_id {
+                        route_times
+                            .entry(route_id.clone())
+                            .or_default()
+                            .push(RouteTime {
+                                seconds: time_diff,
+                                departure_seconds: departure_time
+                                    .map(|t| t as i32 - current_timestamp as i32),
+                                trip_id: trip.trip_id.clone(),
+                                start_time: trip.start_time.clone(),
+                                approximate,
+                            });
+                    }
+                    if trip.trip_id.is_some() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        route_times
+    }
+
+    /// Builds a TrainArrival for a train on `route_id`, with times relative to the `now` timestamp
+    fn build_arrival(&self, route_id: &str, time: RouteTime, now: i64) -> TrainArrival {
+        let RouteTime {
+            seconds,
+            departure_seconds,
+            trip_id,
+            start_time,
+            approximate,
+        } = time;
+        let origin_time = parse_origin_time(start_time.as_deref(), trip_id.as_deref());
+        let is_express = base_route(route_id) != route_id;
+        // Express variants may not be in the static schedule, so fall back to the base route
+        let route_name = self
+            .gtfs
+            .routes
+            .get(route_id)
+            .or_else(|| self.gtfs.routes.get(base_route(route_id)))
+            .and_then(|r| r.short_name.clone());
+        let future_time = DateTime::from_timestamp(now + seconds as i64, 0).unwrap_or_default();
+        let arrival_local = future_time.with_timezone(&MTA_TIMEZONE);
+        let departure_local = departure_seconds.and_then(|departure| {
+            DateTime::from_timestamp(now + departure as i64, 0)
+                .map(|time| time.with_timezone(&MTA_TIMEZONE))
+        });
+        // Relative to `now` rather than the wall clock, so recorded feeds format consistently
+        let human_time = self.time_format.format(seconds as i64);
+        let headsign = trip_id
+            .as_deref()
+            .and_then(|trip_id| self.resolve_trip(trip_id))
+            .and_then(|trip| trip.trip_headsign.clone());
+        TrainArrival {
+            route_id: route_id.to_string(),
+            route_name,
+            arrival_time: seconds,
+            human_time,
+            arrival_local,
+            departure_time: departure_seconds,
+            departure_local,
+            trip_id,
+            is_express,
+            origin_time,
+            headsign,
+            approximate,
+        }
+    }
+
+    /// Gets the current positions of all trains on a route from its realtime feed. Fails
+    /// offline, since positions can't be simulated.
+    pub async fn get_vehicle_positions_for_route(
+        &self,
+        route_id: &str,
+    ) -> Result<Vec<VehiclePosition>> {
+        if self.offline {
+            return Err(anyhow::anyhow!(
+                "Vehicle positions aren't available offline"
+            ));
+        }
+        let routes = HashSet::from([route_id.to_string()]);
+        let feeds = self.get_realtime_feeds_for_routes(&routes)?;
+        let (realtime_feeds, _) = self.fetch_combined_realtime_data(&feeds).await?;
+
+        let mut positions = Vec::new();
+        for feed in &realtime_feeds {
+            for entity in &feed.entity {
+                if let Some(vehicle) = &entity.vehicle
+                    && let Some(trip) = &vehicle.trip
+                    && trip.route_id.as_deref() == Some(route_id)
+                {
+                    positions.push(VehiclePosition {
+                        route_id: route_id.to_string(),
+                        trip_id: trip.trip_id.clone(),
+                        stop_id: vehicle.stop_id.clone(),
+                        stop_name: vehicle
+                            .stop_id
+                            .as_deref()
+                            .and_then(|stop_id| self.get_stop_name(stop_id)),
+                        status: vehicle.current_status().into(),
+                        timestamp: vehicle.timestamp,
+                    });
+                }
+            }
+        }
+
+        Ok(positions)
+    }
+
+    /// Gets the platform stops belonging to a parent station. A stop without children
+    /// (e.g. a platform stop itself) resolves to just that stop.
+    pub fn get_child_stops(&self, parent_stop_id: &str) -> Vec<String> {
+        let mut children: Vec<String> = self
+            .gtfs
+            .stops
+            .values()
+            .filter(|stop| stop.parent_station.as_deref() == Some(parent_stop_id))
+            .map(|stop| stop.id.clone())
+            .collect();
+
+        if children.is_empty() && self.is_valid_stop(parent_stop_id) {
+            children.push(parent_stop_id.to_string());
+        }
+        children.sort();
+        children
+    }
+
+    /// Gets the soonest `limit` arrivals at a station across all of its directions,
+    /// sorted by arrival time with each trip appearing at most once. Fails offline, where
+    /// `get_station_status` has simulated arrivals instead.
+    pub async fn get_next_arrivals(
+        &self,
+        parent_stop_id: &str,
+        limit: usize,
+    ) -> Result<Vec<TrainArrival>> {
+        let child_stops = self.get_child_stops(parent_stop_id);
+        if child_stops.is_empty() {
+            return Err(anyhow::anyhow!("Invalid stop ID: {}", parent_stop_id));
+        }
+        if self.offline {
+            return Err(anyhow::anyhow!("Next arrivals aren't available offline"));
+        }
+
+        // Fetch each feed once for all directions of the station
+        let routes: HashSet<String> = child_stops
+            .iter()
+            .flat_map(|stop_id| self.get_routes_for_stop(stop_id))
+            .collect();
+        let feeds = self.realtime_feeds_for_stop(parent_stop_id, &routes)?;
+        let (realtime_feeds, _) = self.fetch_combined_realtime_data(&feeds).await?;
+
+        let current_timestamp = ChronoUtc::now().timestamp();
+        let mut times: Vec<(RouteTime, String)> = child_stops
+            .iter()
+            .flat_map(|stop_id| {
+                self.collect_route_times(stop_id, &realtime_feeds, current_timestamp, 0)
+            })
+            .flat_map(|(route_id, times)| {
+                times.into_iter().map(move |time| (time, route_id.clone()))
+            })
+            .collect();
+        times.sort();
+
+        let mut seen_trips = HashSet::new();
+        Ok(times
+            .into_iter()
+            .filter(|(time, _)| match &time.trip_id {
+                Some(trip_id) => seen_trips.insert(trip_id.clone()),
+                None => true,
+            })
+            .take(limit)
+            .map(|(time, route_id)| self.build_arrival(&route_id, time, current_timestamp))
+            .collect())
+    }
+
+    /// Gets the status of each platform at a station, fetching each feed once for all of
+    /// them. A stop without platforms gets a single entry for itself.
+    pub async fn get_station_status(
+        &self,
+        parent_stop_id: &str,
+        min_lead_seconds: i32,
+    ) -> Result<StationStatus> {
+        let child_stops = self.get_child_stops(parent_stop_id);
+        if child_stops.is_empty() {
+            return Err(anyhow::anyhow!("Invalid stop ID: {}", parent_stop_id));
+        }
+
+        let now = ChronoUtc::now().timestamp();
+        let platforms = if self.offline {
+            let mut platforms = Vec::new();
+            for stop_id in &child_stops {
+                platforms.push(
+                    self.fetch_stop_status(stop_id, now, min_lead_seconds)
+                        .await?,
+                );
+            }
+            platforms
+        } else {
+            let routes: HashSet<String> = child_stops
+                .iter()
+                .flat_map(|stop_id| self.get_routes_for_stop(stop_id))
+                .collect();
+            let feeds = self.realtime_feeds_for_stop(parent_stop_id, &routes)?;
+            let (realtime_feeds, summary) = self.fetch_combined_realtime_data(&feeds).await?;
+            child_stops
+                .iter()
+                .map(|stop_id| {
+                    let routes = self.get_routes_for_stop(stop_id);
+                    let mut status = self.build_stop_status(
+                        stop_id,
+                        routes,
+                        &realtime_feeds,
+                        now,
+                        min_lead_seconds,
+                    );
+                    status.feeds_total = summary.requested;
+                    status.feeds_succeeded = summary.succeeded;
+                    status
+                })
+                .collect()
+        };
+
+        Ok(StationStatus {
+            stop_id: parent_stop_id.to_string(),
+            stop_name: self.get_stop_name(parent_stop_id),
+            platforms,
         })
     }
 
-    /// Gets the next N arrivals for a specific route at a stop
-    pub async fn get_route_arrivals(
+    /// Gets the next N arrivals for a specific route at a stop
+    pub async fn get_route_arrivals(
+        &self,
+        stop_id: &str,
+        route_id: &str,
+        limit: usize,
+    ) -> Result<Vec<TrainArrival>> {
+        if !self.is_valid_stop(stop_id) {
+            return Err(anyhow::anyhow!("Invalid stop ID: {}", stop_id));
+        }
+
+        // Only fetch the feed carrying this route rather than every feed for the stop
+        let routes = HashSet::from([route_id.to_string()]);
+        let now = ChronoUtc::now().timestamp();
+        let realtime_feeds = if self.offline {
+            vec![Self::simulated_feed(stop_id, &routes, now)]
+        } else {
+            let feeds = self.realtime_feeds_for_stop(stop_id, &routes)?;
+            self.fetch_combined_realtime_data(&feeds).await?.0
+        };
+        Ok(self.route_arrivals(stop_id, route_id, &realtime_feeds, now, limit))
+    }
+
+    /// The soonest `limit` arrivals of a route at a stop in already-fetched feeds, relative
+    /// to the `now` unix timestamp. Like the board, leaves out trains past `max_horizon`.
+    fn route_arrivals(
         &self,
         stop_id: &str,
         route_id: &str,
-        _limit: usize,
-    ) -> Result<Vec<TrainArrival>> {
-        let status = self.get_stop_status(stop_id).await?;
-        Ok(status
-            .train_arrivals
-            .get(route_id)
-            .cloned()
-            .unwrap_or_default())
+        feeds: &[FeedMessage],
+        now: i64,
+        limit: usize,
+    ) -> Vec<TrainArrival> {
+        let mut times = self
+            .collect_route_times(stop_id, feeds, now, 0)
+            .remove(route_id)
+            .unwrap_or_default();
+        self.drop_past_horizon(&mut times);
+        times.sort();
+        times
+            .into_iter()
+            .take(limit)
+            .map(|time| self.build_arrival(route_id, time, now))
+            .collect()
     }
 
     /// Gets all upcoming arrivals at a stop, sorted by arrival time
     pub async fn get_all_arrivals(&self, stop_id: &str) -> Result<Vec<TrainArrival>> {
         let status = self.get_stop_status(stop_id).await?;
-        let mut all_arrivals: Vec<TrainArrival> =
-            status.train_arrivals.values().flatten().cloned().collect();
+        let mut all_arrivals: Vec<TrainArrival> = status.arrivals().cloned().collect();
 
         all_arrivals.sort_by_key(|arrival| arrival.arrival_time);
         Ok(all_arrivals)
     }
 
+    /// Gets the `n` soonest arrivals at a stop across all routes, soonest first. Only
+    /// those `n` are cloned, see `StopStatus::soonest_arrivals`.
+    pub async fn get_soonest_arrivals(&self, stop_id: &str, n: usize) -> Result<Vec<TrainArrival>> {
+        let status = self.get_stop_status(stop_id).await?;
+        Ok(status.soonest_arrivals(n).into_iter().cloned().collect())
+    }
+
     /// Prints GTFS statistics (useful for debugging)
     pub fn print_stats(&self) {
         self.gtfs.print_stats();
@@ -387,7 +2076,36 @@ impl TrainChecker {
 
     /// Formats a stop for display as "Name (Direction)"
     pub fn format_stop_display(&self, stop_id: &str, stop_name: &str) -> String {
-        return format!("{} ({})", stop_name, stop_id);
+        format!("{} ({})", stop_name, stop_id)
+    }
+}
+
+/// How `TrainArrival::human_time` is written. Trains under a minute away read "now" in
+/// every format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeFormat {
+    /// Relative phrases such as "in 3 minutes"
+    #[default]
+    Humanize,
+    /// Whole minutes, e.g. "3 min", for terse countdown boards
+    Minutes,
+    /// Minutes and seconds, e.g. "3m 05s"
+    MinutesSeconds,
+}
+
+impl TimeFormat {
+    /// Formats a time `seconds` from now
+    pub fn format(self, seconds: i64) -> String {
+        if seconds < 60 {
+            return "now".to_string();
+        }
+        match self {
+            TimeFormat::Humanize => {
+                chrono_humanize::HumanTime::from(ChronoDuration::seconds(seconds)).to_string()
+            }
+            TimeFormat::Minutes => format!("{} min", seconds / 60),
+            TimeFormat::MinutesSeconds => format!("{}m {:02}s", seconds / 60, seconds % 60),
+        }
     }
 }
 
@@ -396,28 +2114,144 @@ impl TrainChecker {
 pub struct TrainCheckerConfig {
     pub update_interval: Duration,
     pub max_arrivals_per_route: usize,
+    pub min_interval: Duration,         // fastest allowed update interval
+    pub max_interval: Duration,         // slowest allowed update interval
+    pub interval_step: Duration,        // amount the update interval changes per adjustment
+    pub max_concurrent_requests: usize, // realtime feeds fetched in parallel
+    pub max_requests_per_minute: Option<u32>, // realtime feed requests allowed per minute, if limited
+    pub max_horizon: Option<Duration>,        // hide arrivals further out than this, if set
+    pub approaching_threshold: Duration,      // trains closer than this are shown as arriving now
+    pub feed_base_url: String, // realtime feed URL for the numbered lines, e.g. a mirror
+    pub gtfs_url: String,      // static schedule zip downloaded at startup
+    pub gtfs_auth_header: Option<(String, String)>, // (name, value) sent when downloading the schedule, if set
+    pub gtfs_refresh_interval: Option<Duration>, // how often StopMonitor re-downloads the schedule
+    pub api_key: Option<String>, // sent as x-api-key with realtime feed requests, if set
+    pub time_format: TimeFormat, // how arrivals' human_time is written
+    pub failure_threshold: u32,  // failures in a row on any feed before the status shows Error
 }
 
 impl Default for TrainCheckerConfig {
     fn default() -> Self {
         Self {
             update_interval: Duration::from_secs(10),
-            max_arrivals_per_route: 2,
+            max_arrivals_per_route: 3,
+            min_interval: Duration::from_secs(5),
+            max_interval: Duration::from_secs(120),
+            interval_step: Duration::from_secs(5),
+            max_concurrent_requests: 4,
+            max_requests_per_minute: Some(60),
+            max_horizon: None,
+            approaching_threshold: Duration::from_secs(60),
+            feed_base_url: MTA_SUBWAY_FEED_URL.to_string(),
+            gtfs_url: GTFS_URL.to_string(),
+            gtfs_auth_header: None,
+            gtfs_refresh_interval: None,
+            api_key: None,
+            time_format: TimeFormat::default(),
+            failure_threshold: 3,
         }
     }
 }
 
 /// A monitor that continuously updates stop status
 pub struct StopMonitor {
-    checker: TrainChecker,
+    checker: RwLock<Arc<TrainChecker>>, // swapped out whole when the schedule is refreshed
     config: TrainCheckerConfig,
+    gtfs_cache: Option<PathBuf>, // refreshed schedules are saved here, if set
+    webhook_url: Option<String>, // each update is POSTed here as JSON, if set
+    webhook_failures: AtomicU32, // counted apart from feed failures
 }
 
 impl StopMonitor {
     /// Creates a new stop monitor
     pub async fn new(config: TrainCheckerConfig) -> Result<Self> {
-        let checker = TrainChecker::new().await?;
-        Ok(Self { checker, config })
+        let checker = TrainChecker::new_with_config(&config).await?;
+        Ok(Self::with_checker(checker, config))
+    }
+
+    /// Creates a stop monitor around an already-built checker, e.g. an offline one
+    pub fn with_checker(checker: TrainChecker, config: TrainCheckerConfig) -> Self {
+        let checker = checker.with_config(&config);
+        Self {
+            checker: RwLock::new(Arc::new(checker)),
+            config,
+            gtfs_cache: None,
+            webhook_url: None,
+            webhook_failures: AtomicU32::new(0),
+        }
+    }
+
+    /// Saves schedules downloaded by `gtfs_refresh_interval` refreshes to `cache_path`, so
+    /// offline runs pick them up too
+    pub fn with_gtfs_cache(mut self, cache_path: impl Into<PathBuf>) -> Self {
+        self.gtfs_cache = Some(cache_path.into());
+        self
+    }
+
+    /// The checker currently in use. It's replaced, not modified, by schedule refreshes, so
+    /// a caller holding on to it keeps a consistent schedule.
+    pub fn checker(&self) -> Arc<TrainChecker> {
+        Arc::clone(&self.checker.read().unwrap())
+    }
+
+    /// Downloads the static schedule again and swaps in a checker built from it. On failure
+    /// the current schedule is kept. Request counters start over with the new checker.
+    async fn refresh_gtfs(&self) {
+        let refreshed = match &self.gtfs_cache {
+            Some(cache_path) => TrainChecker::new_with_cache(cache_path, &self.config).await,
+            None => TrainChecker::new_with_config(&self.config).await,
+        };
+        match refreshed {
+            Ok(checker) => {
+                debug!("Refreshed GTFS schedule: {:?}", checker.gtfs_info().version);
+                *self.checker.write().unwrap() = Arc::new(checker);
+            }
+            Err(e) => warn!(
+                "Failed to refresh GTFS schedule, keeping the current one: {}",
+                e
+            ),
+        }
+    }
+
+    /// POSTs every successful update to `url` as JSON, e.g. for a smart display
+    pub fn with_webhook(mut self, url: impl Into<String>) -> Self {
+        self.webhook_url = Some(url.into());
+        self
+    }
+
+    /// Number of updates that couldn't be delivered to the webhook
+    pub fn webhook_failures(&self) -> u32 {
+        self.webhook_failures.load(Ordering::Relaxed)
+    }
+
+    /// Sends a status to the webhook, if one is set. Failures, including a webhook slower
+    /// than `WEBHOOK_TIMEOUT`, are logged and counted, but never interrupt monitoring.
+    async fn deliver_webhook(&self, status: &StopStatus) {
+        let Some(url) = &self.webhook_url else {
+            return;
+        };
+        let client = self.checker().http_client.clone();
+        let result = client
+            .post(url)
+            .json(status)
+            .timeout(WEBHOOK_TIMEOUT)
+            .send()
+            .await;
+        // Webhook URLs often carry a token, so only the host is logged
+        let error = match result {
+            Ok(response) if response.status().is_success() => return,
+            Ok(response) => format!("HTTP error: {}", response.status()),
+            Err(e) => e.without_url().to_string(),
+        };
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+        warn!(
+            "Failed to deliver stop status to webhook at {}: {}",
+            host, error
+        );
+        self.webhook_failures.fetch_add(1, Ordering::Relaxed);
     }
 
     /// Monitors a stop continuously, calling the callback with updates
@@ -425,9 +2259,28 @@ impl StopMonitor {
     where
         F: FnMut(StopStatus) + Send + 'static,
     {
+        let mut last_refresh = Instant::now();
         loop {
-            match self.checker.get_stop_status(stop_id).await {
-                Ok(status) => callback(status),
+            // Offline checkers have no network to refresh from
+            if let Some(refresh_interval) = self.config.gtfs_refresh_interval
+                && last_refresh.elapsed() >= refresh_interval
+                && !self.checker().offline
+            {
+                self.refresh_gtfs().await;
+                last_refresh = Instant::now();
+            }
+
+            let checker = self.checker();
+            match checker.get_stop_status(stop_id).await {
+                Ok(status) => {
+                    // The callback may be what tells a supervisor the monitor is alive, so
+                    // a slow webhook mustn't hold it back
+                    let webhook_status = self.webhook_url.is_some().then(|| status.clone());
+                    callback(status);
+                    if let Some(status) = webhook_status {
+                        self.deliver_webhook(&status).await;
+                    }
+                }
                 Err(e) => warn!("Error getting stop status: {}", e),
             }
 
@@ -435,3 +2288,1050 @@ impl StopMonitor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use subway::trip_update::{StopTimeEvent, StopTimeUpdate};
+    use subway::{FeedEntity, FeedHeader, TripDescriptor, TripUpdate};
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn checker() -> TrainChecker {
+        TrainChecker::from_gtfs(gtfs_structures::Gtfs::default())
+    }
+
+    /// A few Broadway line stops and trips, from tests/fixtures/gtfs
+    fn fixture_checker() -> TrainChecker {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/gtfs");
+        TrainChecker::from_gtfs_path(&path).unwrap()
+    }
+
+    fn arrival_at(stop_id: &str, time: i64) -> StopTimeUpdate {
+        StopTimeUpdate {
+            stop_id: Some(stop_id.to_string()),
+            arrival: Some(StopTimeEvent {
+                time: Some(time),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn trip(trip_id: &str, route_id: &str, updates: Vec<StopTimeUpdate>) -> FeedEntity {
+        FeedEntity {
+            id: trip_id.to_string(),
+            trip_update: Some(TripUpdate {
+                trip: TripDescriptor {
+                    trip_id: Some(trip_id.to_string()),
+                    route_id: Some(route_id.to_string()),
+                    ..Default::default()
+                },
+                stop_time_update: updates,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn feed(entity: Vec<FeedEntity>) -> FeedMessage {
+        FeedMessage {
+            header: FeedHeader {
+                gtfs_realtime_version: "2.0".to_string(),
+                ..Default::default()
+            },
+            entity,
+        }
+    }
+
+    fn checker_with_trips(ids: &[&str]) -> TrainChecker {
+        let mut gtfs = gtfs_structures::Gtfs::default();
+        for id in ids {
+            let trip = gtfs_structures::Trip {
+                id: id.to_string(),
+                ..Default::default()
+            };
+            gtfs.trips.insert(id.to_string(), trip);
+        }
+        TrainChecker::from_gtfs(gtfs)
+    }
+
+    #[test]
+    fn origin_time_from_start_time_or_trip_id() {
+        let time = |h, m, s| NaiveTime::from_hms_opt(h, m, s);
+
+        assert_eq!(parse_origin_time(Some("14:32:00"), None), time(14, 32, 0));
+        assert_eq!(parse_origin_time(Some("25:10:30"), None), time(1, 10, 30));
+        assert_eq!(
+            parse_origin_time(None, Some("068700_1..N03R")),
+            time(11, 27, 0)
+        );
+        assert_eq!(
+            parse_origin_time(None, Some("087350_F..S69R")),
+            time(14, 33, 30)
+        );
+        // The feed's start time wins over the trip ID
+        assert_eq!(
+            parse_origin_time(Some("11:28:00"), Some("068700_1..N03R")),
+            time(11, 28, 0)
+        );
+        assert_eq!(parse_origin_time(Some("soon"), Some("t1")), None);
+        assert_eq!(parse_origin_time(None, None), None);
+    }
+
+    #[test]
+    fn trip_match_key_handles_mta_formats() {
+        assert_eq!(trip_match_key("068700_1..N03R"), Some("068700_1..N"));
+        assert_eq!(
+            trip_match_key("AFA24GEN-1038-Weekday-00_068700_1..N03R"),
+            Some("068700_1..N")
+        );
+        assert_eq!(trip_match_key("121700_A..S"), Some("121700_A..S"));
+        assert_eq!(trip_match_key("047650_GS.N04R"), Some("047650_GS.N"));
+        assert_eq!(trip_match_key("simulated_Q_0"), None);
+    }
+
+    #[test]
+    fn scheduled_arrival_looks_up_the_trips_stop_time() {
+        let checker = fixture_checker();
+        let at = |hour, minute| NaiveTime::from_hms_opt(hour, minute, 0);
+
+        assert_eq!(
+            checker.scheduled_arrival("R16N", "AFA24GEN-N058-Weekday-00_068700_N..N34R"),
+            at(11, 29)
+        );
+        // Realtime trip IDs resolve to their static trip
+        assert_eq!(checker.scheduled_arrival("R15N", "068700_N..N"), at(11, 31));
+        // The trip doesn't stop here, or doesn't exist
+        assert_eq!(checker.scheduled_arrival("R15S", "068700_N..N"), None);
+        assert_eq!(checker.scheduled_arrival("R16N", "999900_N..N"), None);
+    }
+
+    #[test]
+    fn resolve_trip_matches_realtime_ids() {
+        let checker = checker_with_trips(&[
+            "AFA24GEN-1038-Saturday-00_068700_1..N03R",
+            "AFA24GEN-1038-Weekday-00_068700_1..N03R",
+            "AFA24GEN-A092-Weekday-00_121700_A..S55R",
+            "AFA24GEN-GS010-Weekday-00_047650_GS.N04R",
+            "AFA24GEN-1038-Weekday-00_070200_1..N03R",
+        ]);
+        let resolved = |id| checker.resolve_trip(id).map(|trip| trip.id.as_str());
+
+        // Ambiguous service days resolve to the first by ID
+        assert_eq!(
+            resolved("068700_1..N03R"),
+            Some("AFA24GEN-1038-Saturday-00_068700_1..N03R")
+        );
+        // Realtime IDs without the path code still match
+        assert_eq!(
+            resolved("121700_A..S"),
+            Some("AFA24GEN-A092-Weekday-00_121700_A..S55R")
+        );
+        assert_eq!(
+            resolved("047650_GS.N04R"),
+            Some("AFA24GEN-GS010-Weekday-00_047650_GS.N04R")
+        );
+        // Exact static IDs are returned as-is
+        assert_eq!(
+            resolved("AFA24GEN-1038-Weekday-00_068700_1..N03R"),
+            Some("AFA24GEN-1038-Weekday-00_068700_1..N03R")
+        );
+        assert_eq!(resolved("068700_1..S03R"), None);
+        assert_eq!(resolved("068800_1..N03R"), None);
+    }
+
+    #[test]
+    fn fixture_routes_for_stop() {
+        let checker = fixture_checker();
+        let routes = |stop_id| {
+            let mut routes: Vec<String> =
+                checker.get_routes_for_stop(stop_id).into_iter().collect();
+            routes.sort();
+            routes
+        };
+
+        assert_eq!(routes("R16N"), vec!["N", "Q"]);
+        assert_eq!(routes("R16S"), vec!["R"]);
+        assert_eq!(routes("R15N"), vec!["N"]);
+        assert!(routes("R16").is_empty());
+    }
+
+    #[test]
+    fn fixture_stop_lookups() {
+        let checker = fixture_checker();
+
+        assert_eq!(
+            checker.get_stop_id("Times Sq-42 St"),
+            vec!["R16", "R16N", "R16S"]
+        );
+        assert_eq!(
+            checker.get_stop_name("R17S").as_deref(),
+            Some("34 St-Herald Sq")
+        );
+        assert!(checker.is_valid_stop("R15N"));
+        assert!(!checker.is_valid_stop("R99N"));
+        assert_eq!(checker.stop_count(), 9);
+        assert_eq!(
+            checker.process_feeds("R17S", &[], NOW).direction,
+            Some(StopDirection::South)
+        );
+        assert_eq!(
+            checker
+                .resolve_trip("069000_Q..N16R")
+                .and_then(|trip| trip.trip_headsign.as_deref()),
+            Some("96 St")
+        );
+    }
+
+    #[test]
+    fn gtfs_path_without_a_schedule_is_an_error() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/missing");
+        assert!(TrainChecker::from_gtfs_path(&path).is_err());
+    }
+
+    #[test]
+    fn process_feeds_sorts_and_caps_arrivals() {
+        let feeds = [feed(vec![
+            trip("t1", "Q", vec![arrival_at("R16N", NOW + 300)]),
+            trip("t2", "Q", vec![arrival_at("R16N", NOW + 60)]),
+            trip("t3", "Q", vec![arrival_at("R16N", NOW + 600)]),
+            trip("t4", "Q", vec![arrival_at("R16N", NOW + 900)]),
+        ])];
+
+        let status = checker().process_feeds("R16N", &feeds, NOW);
+
+        let times: Vec<i32> = status.train_arrivals["Q"]
+            .iter()
+            .map(|a| a.arrival_time)
+            .collect();
+        assert_eq!(times, vec![60, 300, 600]);
+        assert_eq!(status.train_arrivals["Q"][0].trip_id.as_deref(), Some("t2"));
+    }
+
+    #[test]
+    fn process_feeds_keeps_configured_arrivals_per_route() {
+        let config = TrainCheckerConfig {
+            max_arrivals_per_route: 2,
+            ..TrainCheckerConfig::default()
+        };
+        let feeds = [feed(
+            (1..=5)
+                .map(|n| {
+                    trip(
+                        &format!("t{}", n),
+                        "Q",
+                        vec![arrival_at("R16N", NOW + n * 60)],
+                    )
+                })
+                .collect(),
+        )];
+
+        let status = checker()
+            .with_config(&config)
+            .process_feeds("R16N", &feeds, NOW);
+
+        assert_eq!(status.train_arrivals["Q"].len(), 2);
+    }
+
+    #[test]
+    fn time_format_applies_to_human_time() {
+        assert_eq!(TimeFormat::Minutes.format(30), "now");
+        assert_eq!(TimeFormat::Humanize.format(59), "now");
+        assert_eq!(TimeFormat::Minutes.format(185), "3 min");
+        assert_eq!(TimeFormat::MinutesSeconds.format(185), "3m 05s");
+        assert_eq!(TimeFormat::Humanize.format(180), "in 3 minutes");
+
+        let config = TrainCheckerConfig {
+            time_format: TimeFormat::Minutes,
+            ..TrainCheckerConfig::default()
+        };
+        let feeds = [feed(vec![trip(
+            "t1",
+            "Q",
+            vec![arrival_at("R16N", NOW + 240)],
+        )])];
+        let status = checker()
+            .with_config(&config)
+            .process_feeds("R16N", &feeds, NOW);
+
+        assert_eq!(status.train_arrivals["Q"][0].human_time, "4 min");
+    }
+
+    #[test]
+    fn process_feeds_skips_departed_trains_and_other_stops() {
+        let feeds = [feed(vec![
+            trip("t1", "N", vec![arrival_at("R16N", NOW - 30)]),
+            trip("t2", "N", vec![arrival_at("R17N", NOW + 120)]),
+            trip("t3", "R", vec![arrival_at("R16N", NOW + 90)]),
+        ])];
+
+        let status = checker().process_feeds("R16N", &feeds, NOW);
+
+        assert!(!status.train_arrivals.contains_key("N"));
+        assert_eq!(status.train_arrivals["R"][0].arrival_time, 90);
+    }
+
+    #[test]
+    fn process_feeds_respects_min_lead() {
+        let feeds = [feed(vec![
+            trip("t1", "Q", vec![arrival_at("R16N", NOW + 60)]),
+            trip("t2", "Q", vec![arrival_at("R16N", NOW + 240)]),
+        ])];
+
+        let status = checker().process_feeds_with_lead("R16N", &feeds, NOW, 120);
+
+        let times: Vec<i32> = status.train_arrivals["Q"]
+            .iter()
+            .map(|a| a.arrival_time)
+            .collect();
+        assert_eq!(times, vec![240]);
+    }
+
+    #[test]
+    fn process_feeds_dedupes_trip_across_feeds() {
+        let duplicate = trip("t1", "Q", vec![arrival_at("R16N", NOW + 120)]);
+        let feeds = [
+            feed(vec![duplicate.clone()]),
+            feed(vec![
+                duplicate,
+                trip("t2", "Q", vec![arrival_at("R16N", NOW + 480)]),
+            ]),
+        ];
+
+        let status = checker().process_feeds("R16N", &feeds, NOW);
+
+        let trips: Vec<Option<&str>> = status.train_arrivals["Q"]
+            .iter()
+            .map(|a| a.trip_id.as_deref())
+            .collect();
+        assert_eq!(trips, vec![Some("t1"), Some("t2")]);
+    }
+
+    #[test]
+    fn process_feeds_parses_direction() {
+        let checker = checker();
+        assert_eq!(
+            checker.process_feeds("R16N", &[], NOW).direction,
+            Some(StopDirection::North)
+        );
+        assert_eq!(
+            checker.process_feeds("R16S", &[], NOW).direction,
+            Some(StopDirection::South)
+        );
+        assert_eq!(checker.process_feeds("R16", &[], NOW).direction, None);
+    }
+
+    #[test]
+    fn process_feeds_keeps_departure_times() {
+        let origin = StopTimeUpdate {
+            stop_id: Some("R16N".to_string()),
+            departure: Some(StopTimeEvent {
+                time: Some(NOW + 120),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut through = arrival_at("R16N", NOW + 300);
+        through.departure = Some(StopTimeEvent {
+            time: Some(NOW + 330),
+            ..Default::default()
+        });
+        let feeds = [feed(vec![
+            trip("t1", "Q", vec![origin]),
+            trip("t2", "Q", vec![through]),
+        ])];
+
+        let status = checker().process_feeds("R16N", &feeds, NOW);
+
+        // A train starting its trip here only has a departure, which stands in for its arrival
+        let times: Vec<(i32, Option<i32>)> = status.train_arrivals["Q"]
+            .iter()
+            .map(|a| (a.arrival_time, a.departure_time))
+            .collect();
+        assert_eq!(times, vec![(120, Some(120)), (300, Some(330))]);
+    }
+
+    #[tokio::test]
+    async fn fetch_realtime_data_decodes_gzip_response() {
+        use std::io::Write;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let message = feed(vec![trip("t1", "Q", vec![arrival_at("R16N", NOW + 60)])]);
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&message.encode_to_vec()).unwrap();
+        let body = encoder.finish().unwrap();
+
+        // Serve a single gzip-compressed response, handing back the request it answered
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 4096];
+            let len = socket.read(&mut request).await.unwrap();
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/x-protobuf\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            socket.write_all(head.as_bytes()).await.unwrap();
+            socket.write_all(&body).await.unwrap();
+            String::from_utf8_lossy(&request[..len]).to_lowercase()
+        });
+
+        let client = TrainChecker::build_http_client();
+        let decoded = TrainChecker::fetch_realtime_data(&client, &url, Some("secret"))
+            .await
+            .unwrap();
+
+        assert_eq!(decoded, message);
+        let request = server.await.unwrap();
+        assert!(request.contains("accept-encoding: gzip, deflate"));
+        assert!(request.contains("x-api-key: secret"));
+    }
+
+    #[tokio::test]
+    async fn gtfs_download_streams_to_a_file_with_the_auth_header() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = TrainCheckerConfig {
+            gtfs_url: format!("http://{}/gtfs.zip", listener.local_addr().unwrap()),
+            gtfs_auth_header: Some(("Authorization".to_string(), "Bearer token".to_string())),
+            ..TrainCheckerConfig::default()
+        };
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 4096];
+            let len = socket.read(&mut request).await.unwrap();
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 3\r\nConnection: close\r\n\r\nzip")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&request[..len]).to_lowercase()
+        });
+
+        let dest = temp_download_path();
+        let reported = Mutex::new(Vec::new());
+        TrainChecker::download_gtfs_zip(&config, &dest, &|progress| {
+            reported.lock().unwrap().push(progress)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), b"zip");
+        std::fs::remove_file(&dest).unwrap();
+        let reported = reported.into_inner().unwrap();
+        assert_eq!(reported.first().unwrap().downloaded, 0);
+        let done = *reported.last().unwrap();
+        assert_eq!((done.downloaded, done.total), (3, Some(3)));
+        assert_eq!(done.percent(), Some(100));
+        let request = server.await.unwrap();
+        assert!(request.starts_with("get /gtfs.zip "));
+        assert!(request.contains("authorization: bearer token"));
+    }
+
+    #[tokio::test]
+    async fn webhook_receives_status_and_counts_failures() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Accept a single request, answering with the given status line
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for status_line in ["200 OK", "500 Internal Server Error"] {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                // Headers and the JSON body may arrive in separate reads
+                let mut request = Vec::new();
+                let mut buf = [0; 4096];
+                while request.last() != Some(&b'}') {
+                    let len = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..len]);
+                }
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status_line
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                requests.push(String::from_utf8_lossy(&request).to_string());
+            }
+            requests
+        });
+
+        let monitor =
+            StopMonitor::with_checker(checker(), TrainCheckerConfig::default()).with_webhook(url);
+        let status = monitor.checker().process_feeds("R16N", &[], NOW);
+        monitor.deliver_webhook(&status).await;
+        assert_eq!(monitor.webhook_failures(), 0);
+        monitor.deliver_webhook(&status).await;
+        assert_eq!(monitor.webhook_failures(), 1);
+
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("POST /hook"));
+        assert!(requests[0].contains("\"stop_id\":\"R16N\""));
+        // Webhook failures aren't feed failures
+        assert_eq!(monitor.checker().get_failed_requests_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn feeds_over_the_rate_limit_are_throttled_not_failed() {
+        let config = TrainCheckerConfig {
+            max_requests_per_minute: Some(1),
+            // Nothing listens here, so the one allowed request fails fast
+            feed_base_url: "http://127.0.0.1:1/gtfs".to_string(),
+            ..TrainCheckerConfig::default()
+        };
+        let checker = checker().with_config(&config);
+
+        let feeds = [String::new(), SUFFIX_ACE.to_string()];
+        assert!(checker.fetch_combined_realtime_data(&feeds).await.is_err());
+
+        let health = checker.get_feed_health();
+        assert_eq!((health[""].failures, health[""].throttled), (1, 0));
+        assert_eq!((health["ace"].failures, health["ace"].throttled), (0, 1));
+        assert_eq!(checker.metrics().throttled_requests, 1);
+        assert!(matches!(
+            checker.get_status(),
+            TrainCheckerStatus::Throttled
+        ));
+    }
+
+    #[test]
+    fn status_reflects_recent_failures_not_the_lifetime_count() {
+        let config = TrainCheckerConfig {
+            failure_threshold: 2,
+            ..TrainCheckerConfig::default()
+        };
+        let checker = checker().with_config(&config);
+
+        checker.record_feed_result(SUFFIX_ACE, false);
+        assert!(matches!(checker.get_status(), TrainCheckerStatus::Ok));
+        checker.record_feed_result(SUFFIX_ACE, false);
+        assert!(matches!(checker.get_status(), TrainCheckerStatus::Error));
+
+        checker.record_feed_result(SUFFIX_ACE, true);
+        assert!(matches!(checker.get_status(), TrainCheckerStatus::Ok));
+        // The lifetime counter is kept for diagnostics
+        assert_eq!(checker.get_failed_requests_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn healthcheck_probes_the_feed_without_counting_it() {
+        let config = TrainCheckerConfig {
+            feed_base_url: "http://127.0.0.1:1/gtfs".to_string(),
+            ..TrainCheckerConfig::default()
+        };
+        let checker = fixture_checker().with_config(&config);
+
+        let report = checker.healthcheck().await;
+
+        assert!(report.gtfs_loaded);
+        assert_eq!(report.stop_count, checker.stop_count());
+        assert_eq!(report.feed_reachable, Some(false));
+        assert!(report.feed_error.is_some());
+        assert!(!report.healthy);
+        assert_eq!(checker.metrics().total_requests, 0);
+    }
+
+    #[tokio::test]
+    async fn fetch_summary_covers_only_the_latest_poll() {
+        let config = TrainCheckerConfig {
+            feed_base_url: "http://127.0.0.1:1/gtfs".to_string(),
+            ..TrainCheckerConfig::default()
+        };
+        let checker = checker().with_config(&config);
+        assert_eq!(checker.last_fetch_summary(), None);
+
+        let feeds = [String::new(), SUFFIX_ACE.to_string()];
+        assert!(checker.fetch_combined_realtime_data(&feeds).await.is_err());
+        let expected = FetchSummary {
+            requested: 2,
+            failed: 2,
+            ..FetchSummary::default()
+        };
+        assert_eq!(checker.last_fetch_summary(), Some(expected));
+
+        assert!(
+            checker
+                .fetch_combined_realtime_data(&feeds[..1])
+                .await
+                .is_err()
+        );
+        let summary = checker.last_fetch_summary().unwrap();
+        assert_eq!((summary.requested, summary.failed), (1, 1));
+        assert!(!summary.is_complete());
+        // The lifetime counter keeps counting across polls
+        assert_eq!(checker.get_failed_requests_count(), 3);
+    }
+
+    #[test]
+    fn feed_urls_join_suffixes_onto_any_base() {
+        assert_eq!(feed_url(MTA_SUBWAY_FEED_URL, ""), MTA_SUBWAY_FEED_URL);
+        assert_eq!(
+            feed_url(MTA_SUBWAY_FEED_URL, SUFFIX_ACE),
+            format!("{}-ace", MTA_SUBWAY_FEED_URL)
+        );
+        assert_eq!(
+            feed_url("http://127.0.0.1:8080/gtfs/", SUFFIX_L),
+            "http://127.0.0.1:8080/gtfs-l"
+        );
+    }
+
+    #[test]
+    fn process_feeds_flags_express_trains() {
+        let feeds = [feed(vec![
+            trip("t1", "6", vec![arrival_at("631N", NOW + 60)]),
+            trip("t2", "6X", vec![arrival_at("631N", NOW + 120)]),
+        ])];
+
+        let status = checker().process_feeds("631N", &feeds, NOW);
+
+        assert!(!status.train_arrivals["6"][0].is_express);
+        assert!(status.train_arrivals["6X"][0].is_express);
+    }
+
+    #[test]
+    fn stop_names_shared_by_several_stops_keep_every_id() {
+        let mut gtfs = gtfs_structures::Gtfs::default();
+        for id in ["G22N", "719N", "F09N"] {
+            let stop = gtfs_structures::Stop {
+                id: id.to_string(),
+                name: Some("Court Sq".to_string()),
+                ..Default::default()
+            };
+            gtfs.stops.insert(id.to_string(), Arc::new(stop));
+        }
+
+        let checker = TrainChecker::from_gtfs(gtfs);
+
+        assert_eq!(
+            checker.get_stop_id("Court Sq"),
+            vec!["719N", "F09N", "G22N"]
+        );
+        assert!(checker.get_stop_id("Nowhere").is_empty());
+    }
+
+    #[test]
+    fn process_feeds_drops_arrivals_past_max_horizon() {
+        let config = TrainCheckerConfig {
+            max_horizon: Some(Duration::from_secs(30 * 60)),
+            ..TrainCheckerConfig::default()
+        };
+        let feeds = [feed(vec![
+            trip("t1", "Q", vec![arrival_at("R16N", NOW + 600)]),
+            trip("t2", "Q", vec![arrival_at("R16N", NOW + 40 * 60)]),
+            trip("t3", "R", vec![arrival_at("R16N", NOW + 45 * 60)]),
+        ])];
+
+        let status = checker()
+            .with_config(&config)
+            .process_feeds("R16N", &feeds, NOW);
+
+        assert_eq!(status.train_arrivals["Q"].len(), 1);
+        assert!(!status.train_arrivals.contains_key("R"));
+    }
+
+    #[test]
+    fn route_arrivals_drop_arrivals_past_max_horizon() {
+        let config = TrainCheckerConfig {
+            max_horizon: Some(Duration::from_secs(30 * 60)),
+            ..TrainCheckerConfig::default()
+        };
+        let feeds = [feed(vec![
+            trip("t1", "Q", vec![arrival_at("R16N", NOW + 600)]),
+            trip("t2", "Q", vec![arrival_at("R16N", NOW + 40 * 60)]),
+        ])];
+
+        let arrivals = checker()
+            .with_config(&config)
+            .route_arrivals("R16N", "Q", &feeds, NOW, 5);
+
+        let times: Vec<i32> = arrivals.iter().map(|a| a.arrival_time).collect();
+        assert_eq!(times, vec![600]);
+    }
+
+    #[test]
+    fn get_all_routes_lists_numbers_before_letters() {
+        let mut gtfs = gtfs_structures::Gtfs::default();
+        for id in ["Q", "6X", "GS", "10", "6", "A", "1"] {
+            let route = gtfs_structures::Route {
+                id: id.to_string(),
+                short_name: Some(id.to_string()),
+                ..Default::default()
+            };
+            gtfs.routes.insert(id.to_string(), route);
+        }
+
+        let route_ids: Vec<String> = TrainChecker::from_gtfs(gtfs)
+            .get_all_routes()
+            .into_iter()
+            .map(|route| route.route_id)
+            .collect();
+
+        assert_eq!(route_ids, vec!["1", "6", "6X", "10", "A", "GS", "Q"]);
+    }
+
+    #[test]
+    fn sir_routes_map_to_the_si_feed() {
+        // From routes.txt in the MTA's gtfs_subway.zip:
+        // SI,MTA NYCT,SIR,Staten Island Railway,...
+        let mut gtfs = gtfs_structures::Gtfs::default();
+        for id in ["SI", "SI1"] {
+            let sir = gtfs_structures::Route {
+                id: id.to_string(),
+                short_name: Some("SIR".to_string()),
+                long_name: Some("Staten Island Railway".to_string()),
+                ..Default::default()
+            };
+            gtfs.routes.insert(sir.id.clone(), sir);
+        }
+        let checker = TrainChecker::from_gtfs(gtfs);
+
+        // "SI1" stands in for a variant ID only recognizable from the static schedule
+        for route_id in ["SI", "SIR", "SI1"] {
+            let routes = HashSet::from([route_id.to_string()]);
+            let feeds = checker.get_realtime_feeds_for_routes(&routes).unwrap();
+            assert_eq!(feeds, vec![SUFFIX_SIR], "route {}", route_id);
+        }
+    }
+
+    #[test]
+    fn express_routes_share_their_base_routes_feed() {
+        let not_sir = |_: &str| false;
+        for (route, feed) in [
+            ("6X", ""),
+            ("7X", ""),
+            ("FX", SUFFIX_BDFM),
+            ("AX", SUFFIX_ACE),
+            ("GX", SUFFIX_G),
+            ("JX", SUFFIX_JZ),
+            ("NX", SUFFIX_NQRW),
+            ("LX", SUFFIX_L),
+            ("SIX", SUFFIX_SIR),
+        ] {
+            assert_eq!(
+                route_feed_suffix(route, not_sir),
+                Some(feed),
+                "route {}",
+                route
+            );
+        }
+        // Only a single trailing X marks an express, and a bare "X" isn't a route
+        assert_eq!(route_feed_suffix("6XX", not_sir), None);
+        assert_eq!(route_feed_suffix("X", not_sir), None);
+    }
+
+    #[test]
+    fn feeds_for_stop_lists_feeds_without_fetching() {
+        let checker = fixture_checker();
+
+        assert_eq!(checker.feeds_for_stop("R16N"), vec![SUFFIX_NQRW]);
+        assert!(checker.feeds_for_stop("NOPE").is_empty());
+        assert_eq!(checker.metrics().total_requests, 0);
+    }
+
+    #[test]
+    fn feeds_for_routes_lists_each_feed_once() {
+        let not_sir = |_: &str| false;
+        assert_eq!(feeds_for_routes(["6", "6X", "4", "7X"], not_sir), vec![""]);
+        assert_eq!(
+            feeds_for_routes(["N", "FX", "Q", "T", "F", "R"], not_sir),
+            vec![SUFFIX_NQRW, SUFFIX_BDFM]
+        );
+        assert!(feeds_for_routes(["T", "ZZ"], not_sir).is_empty());
+    }
+
+    #[tokio::test]
+    async fn stops_without_a_known_feed_fail_with_no_feeds_for_stop() {
+        let mut gtfs = gtfs_structures::Gtfs::default();
+        let stop = Arc::new(gtfs_structures::Stop {
+            id: "X01N".to_string(),
+            ..Default::default()
+        });
+        gtfs.stops.insert(stop.id.clone(), Arc::clone(&stop));
+        for (id, route_id) in [("t1", "T"), ("t2", "ZZ")] {
+            let trip = gtfs_structures::Trip {
+                id: id.to_string(),
+                route_id: route_id.to_string(),
+                stop_times: vec![gtfs_structures::StopTime {
+                    stop: Arc::clone(&stop),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            };
+            gtfs.trips.insert(trip.id.clone(), trip);
+        }
+        let checker = TrainChecker::from_gtfs(gtfs);
+
+        let error = checker.get_stop_status("X01N").await.unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<TrainCheckerError>(),
+            Some(&TrainCheckerError::NoFeedsForStop {
+                stop_id: "X01N".to_string(),
+                routes: vec!["T".to_string(), "ZZ".to_string()],
+            })
+        );
+        assert_eq!(
+            error.to_string(),
+            "No live data for stop X01N: no realtime feed carries its routes (T, ZZ)"
+        );
+    }
+
+    #[test]
+    fn headways_are_gaps_between_sorted_arrivals() {
+        let feeds = [feed(vec![
+            trip("t1", "Q", vec![arrival_at("R16N", NOW + 540)]),
+            trip("t2", "Q", vec![arrival_at("R16N", NOW + 120)]),
+            trip("t3", "R", vec![arrival_at("R16N", NOW + 60)]),
+        ])];
+
+        let status = checker().process_feeds("R16N", &feeds, NOW);
+
+        assert_eq!(status.headways("Q"), vec![420]);
+        assert!(status.headways("R").is_empty());
+        assert!(status.headways("W").is_empty());
+    }
+
+    #[test]
+    fn stop_pages_cover_every_stop_in_order() {
+        let mut gtfs = gtfs_structures::Gtfs::default();
+        for id in ["R17N", "R16N", "R16S", "R17S", "R18N"] {
+            let stop = gtfs_structures::Stop {
+                id: id.to_string(),
+                name: Some(format!("Stop {}", id)),
+                ..Default::default()
+            };
+            gtfs.stops.insert(id.to_string(), Arc::new(stop));
+        }
+        let checker = TrainChecker::from_gtfs(gtfs);
+        let ids = |page: Vec<(String, Option<String>)>| -> Vec<String> {
+            page.into_iter().map(|(id, _)| id).collect()
+        };
+
+        assert_eq!(checker.stop_count(), 5);
+        assert_eq!(ids(checker.get_stops_page(0, 2)), vec!["R16N", "R16S"]);
+        assert_eq!(ids(checker.get_stops_page(4, 2)), vec!["R18N"]);
+        assert!(checker.get_stops_page(5, 2).is_empty());
+        assert_eq!(
+            checker.get_stops_page(2, 1),
+            vec![("R17N".to_string(), Some("Stop R17N".to_string()))]
+        );
+        assert_eq!(
+            ids(checker.get_all_stops()),
+            vec!["R16N", "R16S", "R17N", "R17S", "R18N"]
+        );
+    }
+
+    #[test]
+    fn stops_for_route_follow_the_longest_trip() {
+        let stop = |id: &str| {
+            Arc::new(gtfs_structures::Stop {
+                id: id.to_string(),
+                parent_station: Some(id.trim_end_matches(['N', 'S']).to_string()),
+                ..Default::default()
+            })
+        };
+        let trip = |id: &str, route_id: &str, stop_ids: &[&str]| gtfs_structures::Trip {
+            id: id.to_string(),
+            route_id: route_id.to_string(),
+            stop_times: stop_ids
+                .iter()
+                .map(|stop_id| gtfs_structures::StopTime {
+                    stop: stop(stop_id),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let mut gtfs = gtfs_structures::Gtfs::default();
+        for trip in [
+            trip("short_n", "Q", &["R17N", "R16N"]),
+            trip("long_s", "Q", &["R15S", "R16S", "R17S", "R18S"]),
+            trip("other", "R", &["R16N", "R99N"]),
+        ] {
+            gtfs.trips.insert(trip.id.clone(), trip);
+        }
+        let checker = TrainChecker::from_gtfs(gtfs);
+
+        assert_eq!(
+            checker.get_stops_for_route("Q"),
+            vec!["R15S", "R16N", "R16S", "R17N", "R17S", "R18S"]
+        );
+        assert!(checker.get_stops_for_route("W").is_empty());
+    }
+
+    #[test]
+    fn summary_line_lists_soonest_minutes_per_route() {
+        let feeds = [feed(vec![
+            trip("t1", "R", vec![arrival_at("R16N", NOW + 250)]),
+            trip("t2", "Q", vec![arrival_at("R16N", NOW + 130)]),
+            trip("t3", "Q", vec![arrival_at("R16N", NOW + 370)]),
+            trip("t4", "Q", vec![arrival_at("R16N", NOW + 900)]),
+            trip("t5", "N", vec![arrival_at("R16N", NOW + 20)]),
+        ])];
+
+        let status = checker().process_feeds("R16N", &feeds, NOW);
+
+        assert_eq!(status.summary_line(), "R16N  N:0m  Q:2m,6m  R:4m");
+        assert_eq!(
+            checker().process_feeds("R16N", &[], NOW).summary_line(),
+            "R16N"
+        );
+    }
+
+    #[tokio::test]
+    async fn feed_counts_distinguish_missing_data_from_no_trains() {
+        let mut checker = fixture_checker();
+        // Nothing listens here, so every feed fails, which is an error rather than no trains
+        checker.feed_base_url = "http://127.0.0.1:1/gtfs".to_string();
+        assert!(checker.get_stop_status("R16N").await.is_err());
+
+        let status = checker.process_feeds("R16N", &[feed(vec![])], NOW);
+        assert_eq!((status.feeds_succeeded, status.feeds_total), (1, 1));
+        assert!(!status.is_partial());
+
+        let partial = StopStatus {
+            feeds_total: 2,
+            ..status
+        };
+        assert!(partial.is_partial());
+    }
+
+    #[test]
+    fn arrivals_group_by_route_and_headsign() {
+        let feeds = [feed(vec![
+            trip("099900_N..N", "N", vec![arrival_at("R16N", NOW + 60)]),
+            trip("068700_N..N", "N", vec![arrival_at("R16N", NOW + 240)]),
+            trip("069000_Q..N", "Q", vec![arrival_at("R16N", NOW + 120)]),
+        ])];
+
+        let status = fixture_checker().process_feeds("R16N", &feeds, NOW);
+        let groups: Vec<_> = status
+            .arrivals_by_destination()
+            .into_iter()
+            .map(|(key, arrivals)| (key, arrivals.len()))
+            .collect();
+
+        assert_eq!(
+            groups,
+            vec![
+                (("N", None), 1),
+                (("N", Some("Astoria-Ditmars Blvd")), 1),
+                (("Q", Some("96 St")), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn delay_only_updates_are_estimated_from_the_schedule() {
+        let delayed_at = |stop_id: &str| StopTimeUpdate {
+            stop_id: Some(stop_id.to_string()),
+            arrival: Some(StopTimeEvent {
+                delay: Some(120),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut scheduled = trip("068700_N..N", "N", vec![delayed_at("R16N")]);
+        scheduled.trip_update.as_mut().unwrap().trip.start_date = Some("20240501".to_string());
+        // Without a scheduled time there's nothing to add the delay to
+        let unscheduled = trip("099900_N..N", "N", vec![delayed_at("R16N")]);
+
+        // Scheduled at 11:29, two minutes late
+        let expected = MTA_TIMEZONE
+            .with_ymd_and_hms(2024, 5, 1, 11, 31, 0)
+            .unwrap()
+            .timestamp();
+        let status = fixture_checker().process_feeds(
+            "R16N",
+            &[feed(vec![scheduled, unscheduled])],
+            expected - 300,
+        );
+
+        let arrivals = &status.train_arrivals["N"];
+        assert_eq!(arrivals.len(), 1);
+        assert_eq!(arrivals[0].arrival_time, 300);
+        assert!(arrivals[0].approximate);
+    }
+
+    #[tokio::test]
+    async fn station_status_has_a_platform_per_direction() {
+        let mut checker = fixture_checker();
+        checker.offline = true;
+
+        let station = checker.get_station_status("R16", 0).await.unwrap();
+        assert_eq!(station.stop_name.as_deref(), Some("Times Sq-42 St"));
+        let platforms: Vec<(&str, Option<StopDirection>)> = station
+            .platforms
+            .iter()
+            .map(|platform| (platform.stop_id.as_str(), platform.direction))
+            .collect();
+        assert_eq!(
+            platforms,
+            vec![
+                ("R16N", Some(StopDirection::North)),
+                ("R16S", Some(StopDirection::South))
+            ]
+        );
+
+        // A platform is its own only entry
+        let platform = checker.get_station_status("R16N", 0).await.unwrap();
+        assert_eq!(platform.platforms.len(), 1);
+        assert!(checker.get_station_status("X99", 0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn realtime_only_queries_fail_offline_instead_of_coming_back_empty() {
+        let mut checker = fixture_checker();
+        checker.offline = true;
+
+        let error = checker.get_next_arrivals("R16", 3).await.unwrap_err();
+        assert!(error.to_string().contains("offline"));
+        let error = checker
+            .get_vehicle_positions_for_route("Q")
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("offline"));
+    }
+
+    #[test]
+    fn soonest_arrivals_are_the_first_n_across_routes() {
+        let feeds = [feed(vec![
+            trip("t1", "R", vec![arrival_at("R16N", NOW + 250)]),
+            trip("t2", "Q", vec![arrival_at("R16N", NOW + 130)]),
+            trip("t3", "Q", vec![arrival_at("R16N", NOW + 370)]),
+            trip("t4", "N", vec![arrival_at("R16N", NOW + 20)]),
+            trip("t5", "W", vec![arrival_at("R16N", NOW + 250)]),
+        ])];
+        let status = checker().process_feeds("R16N", &feeds, NOW);
+        let soonest = |n| -> Vec<(i32, &str)> {
+            status
+                .soonest_arrivals(n)
+                .into_iter()
+                .map(|arrival| (arrival.arrival_time, arrival.route_id.as_str()))
+                .collect()
+        };
+
+        assert_eq!(soonest(3), vec![(20, "N"), (130, "Q"), (250, "R")]);
+        // Ties are broken by route
+        assert_eq!(soonest(4)[3], (250, "W"));
+        assert_eq!(soonest(10).len(), 5);
+        assert!(soonest(0).is_empty());
+        assert_eq!(status.arrivals().count(), 5);
+    }
+
+    #[test]
+    fn idle_routes_are_scheduled_routes_without_arrivals() {
+        let feeds = [feed(vec![
+            trip("t1", "Q", vec![arrival_at("R16N", NOW + 120)]),
+            trip("t2", "N", vec![arrival_at("R16N", NOW + 300)]),
+        ])];
+
+        let mut status = checker().process_feeds("R16N", &feeds, NOW);
+        status.routes = ["N", "Q", "R", "W"].map(String::from).into();
+
+        assert_eq!(status.live_routes(), vec!["N", "Q"]);
+        assert_eq!(status.idle_routes(), vec!["R", "W"]);
+    }
+}
@@ -4,8 +4,12 @@ use std::sync::OnceLock;
 use anyhow::Result;
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tracing::Level;
 use tracing_error::ErrorLayer;
-use tracing_subscriber::{self, Layer, layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{
+    self, Layer, Registry, filter::EnvFilter, layer::SubscriberExt, reload, util::SubscriberInitExt,
+};
 
 lazy_static! {
     pub static ref PROJECT_NAME: String = env!("CARGO_CRATE_NAME").to_uppercase().to_string();
@@ -19,6 +23,15 @@ lazy_static! {
 // Static variable to store the current log file path
 static LOG_FILE_PATH: OnceLock<PathBuf> = OnceLock::new();
 
+// Handle used to swap the log filter at runtime
+static LOG_FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+// Data directory set explicitly by the embedding app, taking precedence over the defaults
+static DATA_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+// Number of previous session log files kept when a new session starts
+const MAX_OLD_LOG_FILES: usize = 20;
+
 fn project_directory() -> Option<ProjectDirs> {
     // On MacOs this can be found at ~/Library/Application Support/train-checker/
     // On Windows this can be found at C:\Users\<username>\AppData\Local\train-checker\
@@ -27,20 +40,30 @@ fn project_directory() -> Option<ProjectDirs> {
 }
 
 pub fn get_data_dir() -> PathBuf {
-    let directory = if let Some(s) = DATA_FOLDER.clone() {
+    if let Some(dir) = DATA_DIR_OVERRIDE.get() {
+        dir.clone()
+    } else if let Some(s) = DATA_FOLDER.clone() {
         s
     } else if let Some(proj_dirs) = project_directory() {
         proj_dirs.data_local_dir().to_path_buf()
     } else {
         PathBuf::from(".").join(".data")
-    };
-    directory
+    }
 }
 
-pub fn initialize_logging() -> Result<()> {
+/// Initializes logging with logs (and other app data) stored in `dir`. With `None`, the
+/// data directory comes from the `TRAIN_CHECKER_DATA` env var or the platform default.
+pub fn initialize_logging_in(dir: Option<PathBuf>) -> Result<()> {
+    if let Some(dir) = dir {
+        DATA_DIR_OVERRIDE.set(dir).map_err(|_| {
+            anyhow::anyhow!("Failed to set data directory - logger already initialized")
+        })?;
+    }
+
     let session_id = chrono::Utc::now().format("%Y%m%d_%H%M%S").to_string();
     let directory = get_data_dir();
     std::fs::create_dir_all(directory.clone())?;
+    cleanup_old_logs(MAX_OLD_LOG_FILES)?;
 
     // Create log file name with session_id
     let log_file_name = format!("{}_{}.log", env!("CARGO_PKG_NAME"), session_id);
@@ -54,17 +77,22 @@ pub fn initialize_logging() -> Result<()> {
     let log_file = std::fs::File::create(log_path)?;
 
     // Create the environment filter directly instead of setting RUST_LOG
-    let env_filter = tracing_subscriber::filter::EnvFilter::try_from_default_env()
+    let env_filter = EnvFilter::try_from_default_env()
         .or_else(|_| {
             // Try the custom log environment variable
-            std::env::var(LOG_ENV.clone())
-                .map(|level| tracing_subscriber::filter::EnvFilter::new(level))
+            std::env::var(LOG_ENV.clone()).map(EnvFilter::new)
         })
         .unwrap_or_else(|_| {
             // Default to info level for this crate
-            tracing_subscriber::filter::EnvFilter::new(format!("{}=info", env!("CARGO_CRATE_NAME")))
+            crate_filter(Level::INFO)
         });
 
+    // Wrap the filter so the level can be changed while the app is running
+    let (env_filter, filter_handle) = reload::Layer::new(env_filter);
+    LOG_FILTER_HANDLE.set(filter_handle).map_err(|_| {
+        anyhow::anyhow!("Failed to set log filter handle - logger already initialized")
+    })?;
+
     let file_subscriber = tracing_subscriber::fmt::layer()
         .with_file(true)
         .with_line_number(true)
@@ -79,20 +107,105 @@ pub fn initialize_logging() -> Result<()> {
     Ok(())
 }
 
+/// Builds a filter that logs this crate at the given level
+fn crate_filter(level: Level) -> EnvFilter {
+    EnvFilter::new(format!("{}={}", env!("CARGO_CRATE_NAME"), level))
+}
+
+/// Changes the log level of the running logger
+pub fn set_log_level(level: Level) -> Result<()> {
+    let handle = LOG_FILTER_HANDLE
+        .get()
+        .ok_or_else(|| anyhow::anyhow!("Logging not initialized - no log filter available"))?;
+    handle.reload(crate_filter(level))?;
+    Ok(())
+}
+
+/// Removes all but the `keep` most recently modified session log files from the data directory.
+/// Returns the number of files removed.
+pub fn cleanup_old_logs(keep: usize) -> Result<usize> {
+    let prefix = format!("{}_", env!("CARGO_PKG_NAME"));
+
+    let mut log_files: Vec<(PathBuf, std::time::SystemTime)> = std::fs::read_dir(get_data_dir())?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with(&prefix) && name.ends_with(".log")
+        })
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+            Some((entry.path(), modified))
+        })
+        .collect();
+
+    // Newest first, so everything past `keep` is the excess
+    log_files.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+
+    let mut removed = 0;
+    for (path, _) in log_files.into_iter().skip(keep) {
+        if std::fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
 /// Gets the current log file path if logging has been initialized
 pub fn get_log_file_path() -> Option<&'static PathBuf> {
     LOG_FILE_PATH.get()
 }
 
-/// Reads all log entries from the current log file asynchronously
-pub async fn read_log_entries() -> Result<Vec<String>> {
-    let log_path = get_log_file_path()
-        .ok_or_else(|| anyhow::anyhow!("Logging not initialized - no log file path available"))?;
+/// Reads the current log file incrementally. Since the app only appends to the log,
+/// each reload only reads the bytes written since the previous read.
+#[derive(Debug, Default)]
+pub struct LogReader {
+    offset: u64,          // Byte offset of the next unread byte
+    partial_line: String, // Trailing line that hasn't been terminated yet
+    entries: Vec<String>,
+}
 
-    let contents = tokio::fs::read_to_string(log_path).await?;
-    let lines: Vec<String> = contents.lines().map(|line| line.to_string()).collect();
+impl LogReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All log lines read so far
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Reads any lines appended since the last call, returning how many new lines were read
+    pub async fn read_new_entries(&mut self) -> Result<usize> {
+        let log_path = get_log_file_path().ok_or_else(|| {
+            anyhow::anyhow!("Logging not initialized - no log file path available")
+        })?;
+
+        let mut file = tokio::fs::File::open(log_path).await?;
+        let len = file.metadata().await?.len();
+        if len < self.offset {
+            // The file was truncated or replaced, start over
+            *self = Self::default();
+        }
 
-    Ok(lines)
+        file.seek(std::io::SeekFrom::Start(self.offset)).await?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes).await?;
+        self.offset += bytes.len() as u64;
+
+        self.partial_line.push_str(&String::from_utf8_lossy(&bytes));
+        let Some(last_newline) = self.partial_line.rfind('\n') else {
+            return Ok(0);
+        };
+        let remainder = self.partial_line.split_off(last_newline + 1);
+        let complete = std::mem::replace(&mut self.partial_line, remainder);
+
+        let previous_len = self.entries.len();
+        self.entries
+            .extend(complete.lines().map(|line| line.to_string()));
+        Ok(self.entries.len() - previous_len)
+    }
 }
 
 /// Similar to the `std::dbg!` macro, but generates `tracing` events rather
@@ -1,78 +1,285 @@
 use anyhow::Result;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
 use ratatui::{
     DefaultTerminal, Frame,
-    crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    crossterm::event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers,
+        MouseButton, MouseEvent, MouseEventKind,
+    },
     layout::{Alignment, Constraint, Direction, Flex, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::{Line, Text},
-    widgets::{Block, Borders, List, ListItem, ListState, Padding, Paragraph, Wrap},
+    text::{Line, Span, Text},
+    widgets::{
+        Block, Borders, Clear, Gauge, List, ListItem, ListState, Padding, Paragraph, Sparkline,
+        Wrap,
+    },
 };
 
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
-use tracing::info;
-use train_checker::{StopStatus, TrainChecker, TrainCheckerStatus};
+use tokio_util::sync::CancellationToken;
+use tracing::{Level, info};
+use train_checker::{
+    Accessibility, DownloadProgress, FeedHealth, Metrics, RouteInfo, StationStatus, StopDirection,
+    StopMonitor, StopStatus, TrainArrival, TrainChecker, TrainCheckerConfig, TrainCheckerError,
+    TrainCheckerStatus,
+};
 use tui_big_text::{BigText, PixelSize};
 
 mod logger;
+mod storage;
+#[cfg(feature = "systemd")]
+mod systemd;
+
+/// Check train arrival times for a single MTA station direction
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Cli {
+    /// Stop ID to check, e.g. R16N. Without --json or --watch, the TUI opens straight to its board
+    #[arg(long)]
+    stop: Option<String>,
+
+    /// Print the stop's current status as JSON and exit instead of launching the TUI
+    #[arg(long, requires = "stop")]
+    json: bool,
+
+    /// Print a refreshed plain-text arrival board to stdout every interval instead of launching the TUI
+    #[arg(long, requires = "stop", conflicts_with = "json")]
+    watch: bool,
+
+    /// Seconds between refreshes, 15 by default in --watch mode and 10 in the TUI
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    interval: Option<u64>,
+
+    /// API key sent with realtime feed requests
+    #[arg(long)]
+    api_key: Option<String>,
+
+    /// List one entry per station in the TUI, polling both directions at once, instead of
+    /// each N/S platform separately
+    #[arg(long, conflicts_with_all = ["json", "watch"])]
+    stations: bool,
+
+    /// Load GTFS from the disk cache and show simulated arrivals without any network calls
+    #[arg(long)]
+    offline: bool,
+
+    /// Print every stop ID and name, tab-separated, and exit
+    #[arg(long, conflicts_with = "json")]
+    list_stops: bool,
+
+    /// With --list-stops, only print parent stations instead of each N/S platform
+    #[arg(long, requires = "list_stops")]
+    parents_only: bool,
+
+    /// Directory for logs, favorites and the GTFS cache, instead of the platform default
+    #[arg(long)]
+    data_dir: Option<PathBuf>,
+}
+
+// Refresh interval for --watch mode when --interval isn't given
+const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_secs(15);
+
+// Walk time to the platform, adjustable in the polling view
+const WALK_TIME_STEP: Duration = Duration::from_secs(30);
+const MAX_WALK_TIME: Duration = Duration::from_secs(15 * 60);
+
+// Delay before the first retry after a failed poll, doubling with each further failure
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+
+// How often the feed connection is re-warmed while browsing stops, so it's still open
+// (the connection pool drops idle connections after 90s) when a stop is picked
+const WARM_UP_INTERVAL: Duration = Duration::from_secs(60);
+
+// How long a confirmation message replaces the footer
+const FLASH_DURATION: Duration = Duration::from_secs(3);
+
+// How often --watch re-downloads the static schedule
+const GTFS_REFRESH_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+// Arrivals this far out show an empty gauge, which fills as the train approaches
+const GAUGE_HORIZON: Duration = Duration::from_secs(10 * 60);
+
+// Polls of the current stop kept for the trend sparklines, about 10 minutes at the
+// default interval
+const STATUS_HISTORY_LEN: usize = 60;
+
+// Most routes given a trend sparkline row, so the panel can't crowd out the board
+const MAX_TREND_ROWS: u16 = 6;
+
+// Width of the line color legend shown beside the board
+const LEGEND_WIDTH: u16 = 28;
+
+// Smallest terminal the layouts render correctly in
+const MIN_WIDTH: u16 = 60;
+const MIN_HEIGHT: u16 = 15;
+
+/// Everything needed to investigate a reported board, written with the 'e' key
+#[derive(Serialize)]
+struct Snapshot<'a> {
+    taken_at: chrono::DateTime<chrono::Utc>,
+    status: Option<&'a StopStatus>, // the board, when monitoring a single platform
+    station: Option<&'a StationStatus>, // the boards, when monitoring a whole station
+    metrics: Metrics,
+    feeds: Vec<String>, // suffixes of the feeds polled for the stop or station
+    feed_health: std::collections::HashMap<String, FeedHealth>, // feed suffix -> FeedHealth
+}
+
+/// Limits the stop list to one line's stops, in the order the line visits them
+struct LineFilter {
+    label: String,                      // Route short name, e.g. "Q"
+    stop_order: HashMap<String, usize>, // stop_id -> position along the line
+}
 
 #[derive(Debug, Clone)]
 enum AppState {
     Loading,
+    RouteSelection, // Picking a line, to then list only its stops
     Selection,
     Polling { stop_id: String, stop_name: String },
     Log,
 }
 
 enum AppEvent {
-    TrainCheckerReady(TrainChecker),
+    TrainCheckerReady(Box<TrainChecker>),
     TrainCheckerError(String),
-    StopStatusUpdate(StopStatus),
+    GtfsProgress(DownloadProgress), // the schedule download moved along
+    StopStatusUpdate(u64, StopStatus), // (poll_id, status)
+    StationStatusUpdate(u64, StationStatus), // (poll_id, status) when polling a whole station
+    StopStatusError(u64, String),   // (poll_id, error)
+    NoLiveData(u64, String),        // (poll_id, reason) when the stop has no realtime feed at all
     LogFileLoaded,
 }
 
 struct App {
     state: AppState,
-    train_checker: Option<TrainChecker>,
+    train_checker: Option<Arc<TrainChecker>>, // Shared with in-flight poll tasks
+    shutdown: CancellationToken,              // Cancelled on quit to stop background tasks
+    config: TrainCheckerConfig,
+    offline: bool, // Load cached GTFS and simulate arrivals instead of using the network
+    initial_stop: Option<String>, // From --stop, polled as soon as the stops are loaded
+    gtfs_progress: Option<DownloadProgress>, // Schedule download so far, shown while loading
 
     // Selection state
-    stops: Vec<(String, String)>, // (stop_id, stop_name)
+    stops: Vec<(String, String)>, // (stop_id, stop_name), whichever of the lists below is shown
+    platform_stops: Vec<(String, String)>, // Every N/S platform, polled one direction at a time
+    station_stops: Vec<(String, String)>, // Every station, polled in all directions at once
+    station_view: bool,           // When true, stations are listed instead of platforms
     filtered_stops: Vec<usize>,   // indices into stops
     search_input: String,
     list_state: ListState,
+    navigation_mode: bool, // When true, keys navigate the list instead of typing into search
+    favorites: BTreeSet<String>, // Bookmarked stop IDs
+    favorites_only: bool,  // When true, only bookmarked stops are listed
+    stop_list_area: Rect,  // Where the stop list was last rendered, for mapping mouse clicks
+    routes: Vec<RouteInfo>, // Every line, listed in RouteSelection
+    stop_routes: HashMap<String, HashSet<String>>, // stop_id -> routes serving it, for route search
+    route_list_state: ListState,
+    line_filter: Option<LineFilter>, // When set, only the chosen line's stops are listed
 
     // Polling state
     current_stop_status: Option<StopStatus>,
+    current_station_status: Option<StationStatus>, // Set instead when polling a whole station
     polling_interval: Duration,
-    last_update: Option<Instant>,
+    last_update: Option<Instant>,              // Last successful poll
+    last_poll_attempt: Option<Instant>, // Last poll, successful or not. None polls right away
+    poll_failures: u32,                 // Consecutive failed polls, drives the retry backoff
+    last_poll_error: Option<String>,    // Why the latest poll failed, cleared on success
+    no_live_data: Option<String>,       // Why the stop can't be polled at all, which stops polling
+    last_warm_up: Option<Instant>,      // When the feed connection was last pre-warmed
+    refreshing: bool,                   // True while a poll request is in flight
+    poll_id: u64, // Counts polls, results tagged with an older ID are for a stop no longer shown
+    poll_cancel: Option<CancellationToken>, // Cancels the in-flight poll
+    walk_time: Duration, // Trains arriving sooner than this are hidden
+    route_filter: Option<HashSet<String>>, // When set, only these routes are shown
+    show_departures: bool, // Board shows departure instead of arrival times
+    hide_idle_routes: bool, // Scheduled routes without upcoming trains are left off the board
+    show_gauges: bool, // Board shows a countdown gauge per train instead of big text
+    show_clock: bool, // Board adds each train's local clock time to its countdown
+    arrivals_shown: usize, // Trains listed per route, up to config.max_arrivals_per_route
+    show_legend: bool, // Side panel naming the lines that serve the stop
+    show_trends: bool, // Panel of per-route sparklines of the soonest train across polls
+    status_history: VecDeque<Vec<StopStatus>>, // Recent polls by platform, oldest first
 
     // UI state
     should_quit: bool,
     error_message: Option<String>,
     previous_state: Option<AppState>,
+    show_help: bool, // Keybindings popup drawn over the current view
+    flash_message: Option<(String, Instant)>, // Short-lived confirmation and when it was shown
+    clipboard: Option<arboard::Clipboard>, // Kept alive since on Linux copied text is lost when it drops
 
     // Log state
-    file_log_entries: Vec<String>, // Logs read from file
+    log_reader: logger::LogReader, // Logs read from file
     needs_log_reload: bool,        // Flag to trigger log file reload
+    log_scroll: usize,             // Lines scrolled up from the latest log entry
+    log_page_size: usize,          // Visible log lines, updated on render
+    log_level: Level,              // Current level of the file logger
 }
 
 impl App {
-    fn new() -> Self {
+    fn new(offline: bool, config: TrainCheckerConfig, initial_stop: Option<String>) -> Self {
         let app = Self {
             state: AppState::Loading,
             train_checker: None,
+            shutdown: CancellationToken::new(),
             stops: Vec::new(),
+            platform_stops: Vec::new(),
+            station_stops: Vec::new(),
+            station_view: false,
             filtered_stops: Vec::new(),
             search_input: String::new(),
             list_state: ListState::default(),
+            navigation_mode: false,
+            favorites: storage::load_favorites(),
+            favorites_only: false,
+            stop_list_area: Rect::default(),
+            routes: Vec::new(),
+            stop_routes: HashMap::new(),
+            route_list_state: ListState::default(),
+            line_filter: None,
             current_stop_status: None,
-            polling_interval: Duration::from_secs(10),
+            current_station_status: None,
+            polling_interval: config.update_interval,
             last_update: None,
+            last_poll_attempt: None,
+            poll_failures: 0,
+            last_poll_error: None,
+            no_live_data: None,
+            last_warm_up: None,
+            refreshing: false,
+            poll_id: 0,
+            poll_cancel: None,
+            walk_time: Duration::ZERO,
+            route_filter: None,
+            show_departures: false,
+            hide_idle_routes: false,
+            show_gauges: false,
+            show_clock: false,
+            arrivals_shown: config.max_arrivals_per_route.max(1),
+            show_legend: false,
+            show_trends: false,
+            status_history: VecDeque::with_capacity(STATUS_HISTORY_LEN),
             should_quit: false,
             error_message: None,
             previous_state: None,
-            file_log_entries: Vec::new(),
+            show_help: false,
+            flash_message: None,
+            clipboard: None,
+            log_reader: logger::LogReader::new(),
             needs_log_reload: false,
+            log_scroll: 0,
+            log_page_size: 10,
+            log_level: Level::INFO,
+            config,
+            offline,
+            initial_stop,
+            gtfs_progress: None,
         };
         app.log("Application initialized".to_string());
         app
@@ -83,12 +290,15 @@ impl App {
         info!("{}", message);
     }
 
-    /// Load logs from the file and update the file_log_entries field
+    /// Load any new logs from the file into the log reader
     async fn update_file_logs(&mut self) -> Result<()> {
-        match logger::read_log_entries().await {
-            Ok(logs) => {
-                self.file_log_entries = logs;
-                self.log("Loaded logs from file".to_string());
+        match self.log_reader.read_new_entries().await {
+            Ok(new_lines) => {
+                if new_lines > 0 {
+                    // Snap to the latest line when new logs arrive
+                    self.log_scroll = 0;
+                }
+                self.log(format!("Loaded {} new log lines from file", new_lines));
                 Ok(())
             }
             Err(e) => {
@@ -105,41 +315,97 @@ impl App {
             return;
         }
 
+        // The help popup swallows keys until it is dismissed
+        if self.show_help {
+            if matches!(key.code, KeyCode::Char('?') | KeyCode::Esc) {
+                self.show_help = false;
+            }
+            return;
+        }
+        if key.code == KeyCode::Char('?') {
+            self.show_help = true;
+            return;
+        }
+
+        // 'q' quits everywhere except while typing a search, where it's a literal character
+        let typing_search = matches!(self.state, AppState::Selection) && !self.navigation_mode;
+        if key.code == KeyCode::Char('q') && !typing_search {
+            self.should_quit = true;
+            return;
+        }
+
         match &self.state {
             AppState::Loading => {}
+            AppState::RouteSelection => match key.code {
+                KeyCode::Enter => self.open_selected_line(),
+                KeyCode::Esc | KeyCode::BackTab => {
+                    self.state = AppState::Selection;
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    let selected = self.route_list_state.selected().unwrap_or(0);
+                    self.route_list_state
+                        .select(Some(selected.saturating_sub(1)));
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let selected = self.route_list_state.selected().map_or(0, |i| i + 1);
+                    let selected = clamp_selection(Some(selected), self.routes.len());
+                    self.route_list_state.select(selected);
+                }
+                _ => {}
+            },
             AppState::Selection => match key.code {
-                KeyCode::Enter => {
-                    if let Some(selected) = self.list_state.selected() {
-                        if selected < self.filtered_stops.len() {
-                            let stop_index = self.filtered_stops[selected];
-                            let (stop_id, display_name) = &self.stops[stop_index];
-                            self.log(format!("Selected stop: {} ({})", display_name, stop_id));
-                            self.state = AppState::Polling {
-                                stop_id: stop_id.clone(),
-                                stop_name: display_name.clone(),
-                            };
-                            self.current_stop_status = None;
-                            self.last_update = None;
-                        }
-                    }
+                KeyCode::Enter => self.open_selected_stop(),
+                KeyCode::Esc => {
+                    // Toggle between typing into search and navigating the list
+                    self.navigation_mode = !self.navigation_mode;
+                }
+                KeyCode::Up => self.select_previous_stop(),
+                KeyCode::Down => self.select_next_stop(),
+                KeyCode::PageUp => self.move_stop_selection(-(self.stop_page_size() as isize)),
+                KeyCode::PageDown => self.move_stop_selection(self.stop_page_size() as isize),
+                KeyCode::Home => self.move_stop_selection(isize::MIN),
+                KeyCode::End => self.move_stop_selection(isize::MAX),
+                KeyCode::Char('k') if self.navigation_mode => self.select_previous_stop(),
+                KeyCode::Char('j') if self.navigation_mode => self.select_next_stop(),
+                KeyCode::Char('/') if self.navigation_mode => {
+                    self.navigation_mode = false;
                 }
-                KeyCode::Up => {
-                    let selected = self.list_state.selected().unwrap_or(0);
-                    if selected > 0 {
-                        self.list_state.select(Some(selected - 1));
+                KeyCode::Char('f') if self.navigation_mode => {
+                    if let Some(selected) = self.list_state.selected()
+                        && selected < self.filtered_stops.len()
+                    {
+                        let stop_id = self.stops[self.filtered_stops[selected]].0.clone();
+                        self.toggle_favorite(&stop_id);
+                        self.filter_stops();
                     }
                 }
-                KeyCode::Down => {
-                    let selected = self.list_state.selected().unwrap_or(0);
-                    if selected + 1 < self.filtered_stops.len() {
-                        self.list_state.select(Some(selected + 1));
+                KeyCode::Char('p') if self.navigation_mode => {
+                    self.station_view = !self.station_view;
+                    self.show_stop_list();
+                    self.flash(if self.station_view {
+                        "Listing stations, both directions".to_string()
+                    } else {
+                        "Listing single-direction platforms".to_string()
+                    });
+                }
+                KeyCode::Tab => {
+                    // Switch between all stops and the favorites view
+                    self.favorites_only = !self.favorites_only;
+                    self.filter_stops();
+                }
+                KeyCode::BackTab => {
+                    // Switch between all stops and picking a line first
+                    if self.line_filter.take().is_some() {
+                        self.filter_stops();
+                    } else {
+                        self.state = AppState::RouteSelection;
                     }
                 }
-                KeyCode::Backspace => {
+                KeyCode::Backspace if !self.navigation_mode => {
                     self.search_input.pop();
                     self.filter_stops();
                 }
-                KeyCode::Char(c) => {
+                KeyCode::Char(c) if !self.navigation_mode => {
                     self.search_input.push(c);
                     self.filter_stops();
                 }
@@ -149,30 +415,86 @@ impl App {
                 match key.code {
                     KeyCode::Char('s') => {
                         self.log("Returning to stop selection".to_string());
+                        self.cancel_poll();
                         self.state = AppState::Selection;
                         self.current_stop_status = None;
+                        self.current_station_status = None;
+                        self.navigation_mode = false;
                         self.search_input.clear();
                         self.filter_stops();
                     }
+                    KeyCode::Char('r') => {
+                        // Force an immediate poll on the next loop iteration
+                        self.log("Manual refresh requested".to_string());
+                        self.last_poll_attempt = None;
+                    }
+                    KeyCode::Char('f') => {
+                        if let Some(stop_id) = self.get_current_stop_id() {
+                            let stop_id = stop_id.to_string();
+                            self.toggle_favorite(&stop_id);
+                        }
+                    }
+                    KeyCode::Char('o') => self.cycle_route_filter(),
+                    KeyCode::Char('y') => self.copy_stop_id(),
+                    KeyCode::Char('e') => self.export_snapshot(),
+                    KeyCode::Char('d') => {
+                        self.show_departures = !self.show_departures;
+                        self.log(format!("Showing departures: {}", self.show_departures));
+                    }
+                    KeyCode::Char('a') => {
+                        // Cycle 1, 2, ... up to however many trains are fetched per route
+                        self.arrivals_shown =
+                            self.arrivals_shown % self.config.max_arrivals_per_route.max(1) + 1;
+                        self.flash(format!("Showing {} trains per route", self.arrivals_shown));
+                    }
+                    KeyCode::Char('c') => self.show_legend = !self.show_legend,
+                    KeyCode::Char('t') => self.show_trends = !self.show_trends,
+                    KeyCode::Char('g') => {
+                        self.show_gauges = !self.show_gauges;
+                        self.log(format!("Showing gauges: {}", self.show_gauges));
+                    }
+                    KeyCode::Char('w') => {
+                        self.show_clock = !self.show_clock;
+                        self.log(format!("Showing clock times: {}", self.show_clock));
+                    }
+                    KeyCode::Char('n') => {
+                        self.hide_idle_routes = !self.hide_idle_routes;
+                        self.log(format!("Hiding idle routes: {}", self.hide_idle_routes));
+                    }
                     KeyCode::Char('l') | KeyCode::Char('L') => {
                         self.log("Entering log mode from polling".to_string());
                         self.previous_state = Some(self.state.clone());
                         self.state = AppState::Log;
                         self.needs_log_reload = true; // Trigger log file reload
                     }
+                    // '=' is '+' without Shift on most keyboards
                     KeyCode::Char('+') | KeyCode::Char('=') => {
                         // Decrease polling interval (faster)
-                        if self.polling_interval > Duration::from_secs(5) {
-                            self.polling_interval =
-                                Duration::from_secs((self.polling_interval.as_secs() - 5).max(5));
-                        }
+                        self.polling_interval = adjust_interval(
+                            self.polling_interval,
+                            -(self.config.interval_step.as_secs() as i64),
+                            self.config.min_interval,
+                            self.config.max_interval,
+                        );
+                    }
+                    KeyCode::Char(']') if self.walk_time < MAX_WALK_TIME => {
+                        // Increase walk time to hide more of the soonest trains
+                        self.walk_time = (self.walk_time + WALK_TIME_STEP).min(MAX_WALK_TIME);
+                        self.last_poll_attempt = None;
+                    }
+                    KeyCode::Char('[') if !self.walk_time.is_zero() => {
+                        // Decrease walk time
+                        self.walk_time = self.walk_time.saturating_sub(WALK_TIME_STEP);
+                        self.last_poll_attempt = None;
                     }
                     KeyCode::Char('-') => {
                         // Increase polling interval (slower)
-                        if self.polling_interval < Duration::from_secs(120) {
-                            self.polling_interval =
-                                Duration::from_secs((self.polling_interval.as_secs() + 5).min(120));
-                        }
+                        self.polling_interval = adjust_interval(
+                            self.polling_interval,
+                            self.config.interval_step.as_secs() as i64,
+                            self.config.min_interval,
+                            self.config.max_interval,
+                        );
                     }
                     _ => {}
                 }
@@ -186,97 +508,628 @@ impl App {
                         self.state = previous;
                         self.previous_state = None;
                     }
+                    KeyCode::Char('d') => {
+                        // Toggle between INFO and DEBUG logging
+                        let level = if self.log_level == Level::DEBUG {
+                            Level::INFO
+                        } else {
+                            Level::DEBUG
+                        };
+                        match logger::set_log_level(level) {
+                            Ok(()) => {
+                                self.log_level = level;
+                                self.log(format!("Log level set to {}", level));
+                                self.needs_log_reload = true;
+                            }
+                            Err(e) => self.log(format!("Failed to set log level: {}", e)),
+                        }
+                    }
+                    KeyCode::Up => self.scroll_log_up(1),
+                    KeyCode::Down => self.scroll_log_down(1),
+                    KeyCode::PageUp => self.scroll_log_up(self.log_page_size),
+                    KeyCode::PageDown => self.scroll_log_down(self.log_page_size),
                     _ => {
-                        // In log mode, most keys are ignored (Ctrl-C handled globally above)
+                        // In log mode, most keys are ignored (quit keys handled globally above)
                     }
                 }
             }
         }
     }
 
+    /// Switches to polling the given stop and remembers it for the next run
+    fn start_polling(&mut self, stop_id: String, stop_name: String) {
+        if let Err(e) = storage::save_last_stop(&stop_id) {
+            self.log(format!("Failed to save last stop: {}", e));
+        }
+        // A poll still running for the previous stop would otherwise land on this one's board
+        self.cancel_poll();
+        self.state = AppState::Polling { stop_id, stop_name };
+        self.current_stop_status = None;
+        self.current_station_status = None;
+        self.last_update = None;
+        self.last_poll_attempt = None;
+        self.poll_failures = 0;
+        self.last_poll_error = None;
+        self.no_live_data = None;
+        self.route_filter = None;
+        self.status_history.clear();
+    }
+
+    /// Adds a poll of every monitored platform to the trend history, dropping the oldest
+    /// once it's full
+    fn record_status(&mut self, platforms: Vec<StopStatus>) {
+        if self.status_history.len() == STATUS_HISTORY_LEN {
+            self.status_history.pop_front();
+        }
+        self.status_history.push_back(platforms);
+    }
+
+    /// Copies the monitored stop ID to the system clipboard
+    fn copy_stop_id(&mut self) {
+        let Some(stop_id) = self.get_current_stop_id().map(str::to_string) else {
+            return;
+        };
+
+        // There may be no clipboard at all, e.g. over SSH or on a headless Pi
+        if self.clipboard.is_none() {
+            match arboard::Clipboard::new() {
+                Ok(clipboard) => self.clipboard = Some(clipboard),
+                Err(e) => {
+                    self.log(format!("Clipboard unavailable: {}", e));
+                    self.flash("Clipboard unavailable".to_string());
+                    return;
+                }
+            }
+        }
+        let result = self
+            .clipboard
+            .as_mut()
+            .map_or(Ok(()), |clipboard| clipboard.set_text(&stop_id));
+        match result {
+            Ok(()) => {
+                self.log(format!("Copied stop ID {} to clipboard", stop_id));
+                self.flash(format!("Copied {} to clipboard", stop_id));
+            }
+            Err(e) => {
+                self.log(format!(
+                    "Failed to copy stop ID {} to clipboard: {}",
+                    stop_id, e
+                ));
+                self.flash("Clipboard unavailable".to_string());
+            }
+        }
+    }
+
+    /// Writes the current board and feed stats to a JSON file for bug reports
+    fn export_snapshot(&mut self) {
+        let Some((stop_id, snapshot)) = self.snapshot() else {
+            self.flash("Nothing to export yet".to_string());
+            return;
+        };
+        let saved = storage::save_snapshot(stop_id, snapshot.taken_at, &snapshot);
+        match saved {
+            Ok(path) => {
+                self.log(format!("Exported snapshot to {}", path.display()));
+                self.flash(format!("Saved snapshot to {}", path.display()));
+            }
+            Err(e) => {
+                self.log(format!("Failed to export snapshot: {}", e));
+                self.flash("Failed to save snapshot, see log".to_string());
+            }
+        }
+    }
+
+    /// The board being monitored with the checker's stats, and the stop or station ID to
+    /// name the file after. None until the first poll completes.
+    fn snapshot(&self) -> Option<(&str, Snapshot<'_>)> {
+        let checker = self.train_checker.as_ref()?;
+        let (stop_id, status, station) =
+            match (&self.current_station_status, &self.current_stop_status) {
+                (Some(station), _) => (station.stop_id.as_str(), None, Some(station)),
+                (None, Some(status)) => (status.stop_id.as_str(), Some(status), None),
+                (None, None) => return None,
+            };
+
+        let mut feeds: Vec<String> = self
+            .monitored_statuses()
+            .into_iter()
+            .flat_map(|status| checker.feeds_for_stop(&status.stop_id))
+            .collect();
+        feeds.sort();
+        feeds.dedup();
+        let snapshot = Snapshot {
+            taken_at: chrono::Utc::now(),
+            status,
+            station,
+            metrics: checker.metrics(),
+            feeds,
+            feed_health: checker.get_feed_health(),
+        };
+        Some((stop_id, snapshot))
+    }
+
+    /// Shows a message in place of the footer for `FLASH_DURATION`
+    fn flash(&mut self, message: String) {
+        self.flash_message = Some((message, Instant::now()));
+    }
+
+    /// The flash message, if it hasn't expired yet
+    fn active_flash(&self) -> Option<&str> {
+        self.flash_message
+            .as_ref()
+            .filter(|(_, shown_at)| shown_at.elapsed() < FLASH_DURATION)
+            .map(|(message, _)| message.as_str())
+    }
+
+    /// Steps the route filter through each route serving the stop, then back to all routes
+    fn cycle_route_filter(&mut self) {
+        let mut routes: Vec<&str> = self.served_routes().into_iter().collect();
+        routes.sort();
+
+        let next_index = match &self.route_filter {
+            None => 0,
+            Some(filter) => routes
+                .iter()
+                .position(|route| filter.contains(*route))
+                .map_or(0, |i| i + 1),
+        };
+        self.route_filter = routes
+            .get(next_index)
+            .map(|route| HashSet::from([route.to_string()]));
+        self.log(format!("Route filter set to {:?}", self.route_filter));
+    }
+
+    /// The latest status of the monitored stop, or of each platform of the monitored station
+    fn monitored_statuses(&self) -> Vec<&StopStatus> {
+        match &self.current_station_status {
+            Some(station) => station.platforms.iter().collect(),
+            None => self.current_stop_status.iter().collect(),
+        }
+    }
+
+    /// Routes serving the monitored stop, or any platform of the monitored station
+    fn served_routes(&self) -> HashSet<&str> {
+        self.monitored_statuses()
+            .into_iter()
+            .flat_map(|status| status.routes.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// The route filter formatted for display, e.g. "Q,B"
+    fn route_filter_label(&self) -> Option<String> {
+        let filter = self.route_filter.as_ref()?;
+        let mut routes: Vec<&str> = filter.iter().map(String::as_str).collect();
+        routes.sort();
+        Some(routes.join(","))
+    }
+
+    /// Adds or removes a stop from the favorites and persists the change
+    fn toggle_favorite(&mut self, stop_id: &str) {
+        if self.favorites.remove(stop_id) {
+            self.log(format!("Removed favorite: {}", stop_id));
+        } else {
+            self.favorites.insert(stop_id.to_string());
+            self.log(format!("Added favorite: {}", stop_id));
+        }
+        if let Err(e) = storage::save_favorites(&self.favorites) {
+            self.log(format!("Failed to save favorites: {}", e));
+        }
+    }
+
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) {
+        if self.show_help || !matches!(self.state, AppState::Selection) {
+            return;
+        }
+
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.select_previous_stop(),
+            MouseEventKind::ScrollDown => self.select_next_stop(),
+            MouseEventKind::Down(MouseButton::Left) => {
+                // Rows inside the list's borders, shifted by how far the list has scrolled
+                let area = self.stop_list_area;
+                let inside = mouse.column > area.x
+                    && mouse.column < area.right().saturating_sub(1)
+                    && mouse.row > area.y
+                    && mouse.row < area.bottom().saturating_sub(1);
+                if !inside {
+                    return;
+                }
+                let index = (mouse.row - area.y - 1) as usize + self.list_state.offset();
+                if index >= self.filtered_stops.len() {
+                    return;
+                }
+
+                // Clicking the highlighted stop opens it, like pressing Enter
+                if self.list_state.selected() == Some(index) {
+                    self.open_selected_stop();
+                } else {
+                    self.list_state.select(Some(index));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Lists only the highlighted line's stops in the selection list
+    fn open_selected_line(&mut self) {
+        let (Some(route), Some(checker)) = (
+            self.route_list_state
+                .selected()
+                .and_then(|selected| self.routes.get(selected)),
+            &self.train_checker,
+        ) else {
+            return;
+        };
+
+        // Stations take the position of their first platform, so either list can be filtered
+        let mut stop_order = HashMap::new();
+        for (position, stop_id) in checker
+            .get_stops_for_route(&route.route_id)
+            .into_iter()
+            .enumerate()
+        {
+            if let Some(station) = stop_id.strip_suffix(['N', 'S']) {
+                stop_order.entry(station.to_string()).or_insert(position);
+            }
+            stop_order.insert(stop_id, position);
+        }
+        let label = route
+            .short_name
+            .clone()
+            .unwrap_or_else(|| route.route_id.clone());
+        self.log(format!("Listing stops on the {} line", label));
+        self.line_filter = Some(LineFilter { label, stop_order });
+        self.state = AppState::Selection;
+        self.search_input.clear();
+        self.list_state.select(Some(0));
+        self.filter_stops();
+    }
+
+    /// Starts monitoring the highlighted stop in the selection list
+    fn open_selected_stop(&mut self) {
+        if let Some(selected) = self.list_state.selected()
+            && selected < self.filtered_stops.len()
+        {
+            let stop_index = self.filtered_stops[selected];
+            let (stop_id, display_name) = self.stops[stop_index].clone();
+            self.log(format!("Selected stop: {} ({})", display_name, stop_id));
+            self.start_polling(stop_id, display_name);
+        }
+    }
+
+    fn select_previous_stop(&mut self) {
+        let selected = self.list_state.selected().unwrap_or(0);
+        if selected > 0 {
+            self.list_state.select(Some(selected - 1));
+        }
+    }
+
+    fn select_next_stop(&mut self) {
+        let selected = self.list_state.selected().unwrap_or(0);
+        if selected + 1 < self.filtered_stops.len() {
+            self.list_state.select(Some(selected + 1));
+        }
+    }
+
+    /// Stop list rows visible inside the list's borders, as last rendered
+    fn stop_page_size(&self) -> usize {
+        self.stop_list_area.height.saturating_sub(2).max(1) as usize
+    }
+
+    /// Moves the stop selection by up to `rows`, stopping at either end of the list
+    fn move_stop_selection(&mut self, rows: isize) {
+        if self.filtered_stops.is_empty() {
+            return;
+        }
+        let last = self.filtered_stops.len() - 1;
+        let selected = self.list_state.selected().unwrap_or(0);
+        self.list_state
+            .select(Some(selected.saturating_add_signed(rows).min(last)));
+    }
+
+    /// Scrolls the log view towards older entries, stopping at the first line
+    fn scroll_log_up(&mut self, lines: usize) {
+        let max_scroll = self.log_reader.entries().len().saturating_sub(1);
+        self.log_scroll = (self.log_scroll + lines).min(max_scroll);
+    }
+
+    /// Scrolls the log view towards newer entries, stopping at the latest line
+    fn scroll_log_down(&mut self, lines: usize) {
+        self.log_scroll = self.log_scroll.saturating_sub(lines);
+    }
+
     fn handle_app_event(&mut self, event: AppEvent) {
         match event {
             AppEvent::TrainCheckerReady(checker) => {
+                let checker = checker.with_config(&self.config);
                 self.log("TrainChecker ready, loading stops".to_string());
-                let stops: Vec<(String, String)> = checker
-                    .get_all_stops()
-                    .into_iter()
-                    .filter_map(|(id, name)| {
-                        // Only include stops that end with 'N' or 'S'
-                        if id.ends_with('N') || id.ends_with('S') {
+                let display = |stops: Vec<(String, Option<String>)>| -> Vec<(String, String)> {
+                    stops
+                        .into_iter()
+                        .map(|(id, name)| {
                             let stop_name = name.unwrap_or_else(|| "Unknown".to_string());
                             let display_name = checker.format_stop_display(&id, &stop_name);
-                            Some((id, display_name))
-                        } else {
-                            None
-                        }
-                    })
+                            (id, display_name)
+                        })
+                        .collect()
+                };
+                self.platform_stops = display(selectable_stops(checker.get_all_stops()));
+                self.station_stops = display(checker.get_parent_stations());
+
+                self.log(format!(
+                    "Loaded {} platforms at {} stations",
+                    self.platform_stops.len(),
+                    self.station_stops.len()
+                ));
+
+                // Drop bookmarked stops that no longer exist in the downloaded GTFS
+                let favorites_count = self.favorites.len();
+                let known = |id: &str| {
+                    self.platform_stops
+                        .iter()
+                        .chain(&self.station_stops)
+                        .any(|(stop_id, _)| stop_id == id)
+                };
+                let favorites = std::mem::take(&mut self.favorites);
+                self.favorites = favorites
+                    .into_iter()
+                    .filter(|favorite| known(favorite))
                     .collect();
+                if self.favorites.len() != favorites_count {
+                    self.log(format!(
+                        "Dropped {} favorites that no longer exist",
+                        favorites_count - self.favorites.len()
+                    ));
+                    if let Err(e) = storage::save_favorites(&self.favorites) {
+                        self.log(format!("Failed to save favorites: {}", e));
+                    }
+                }
 
-                self.log(format!("Loaded {} stops", stops.len()));
-                self.stops = stops;
-                self.train_checker = Some(checker);
+                // A station is served by every route stopping at its platforms
+                self.stop_routes = self
+                    .platform_stops
+                    .iter()
+                    .chain(&self.station_stops)
+                    .map(|(id, _)| {
+                        let routes = checker
+                            .get_child_stops(id)
+                            .iter()
+                            .flat_map(|child| checker.get_routes_for_stop(child))
+                            .collect();
+                        (id.clone(), routes)
+                    })
+                    .collect();
+                self.routes = checker.get_all_routes();
+                self.route_list_state
+                    .select(clamp_selection(Some(0), self.routes.len()));
+                self.train_checker = Some(Arc::new(checker));
                 self.state = AppState::Selection;
-                self.filter_stops();
+                self.show_stop_list();
+
+                // A stop given on the command line wins over the last polled one
+                if let Some(stop_id) = self.initial_stop.take() {
+                    match self.find_stop(&stop_id) {
+                        Some((stop_id, display_name)) => {
+                            self.log(format!("Opening stop from --stop: {}", display_name));
+                            self.start_polling(stop_id, display_name);
+                        }
+                        None => {
+                            self.log(format!("Unknown stop from --stop: {}", stop_id));
+                            self.flash(format!("Unknown stop ID: {}", stop_id));
+                        }
+                    }
+                } else if let Some(last_stop_id) = storage::load_last_stop() {
+                    // Jump straight back to the last polled stop if it still exists
+                    match self.find_stop(&last_stop_id) {
+                        Some((stop_id, display_name)) => {
+                            self.log(format!("Restoring last stop: {}", display_name));
+                            self.start_polling(stop_id, display_name);
+                        }
+                        None => {
+                            self.log(format!("Ignoring unknown last stop: {}", last_stop_id));
+                        }
+                    }
+                }
             }
             AppEvent::TrainCheckerError(error) => {
                 self.log(format!("TrainChecker error: {}", error));
                 self.error_message = Some(error);
             }
-            AppEvent::StopStatusUpdate(status) => {
+            AppEvent::GtfsProgress(progress) => {
+                self.gtfs_progress = Some(progress);
+            }
+            AppEvent::StopStatusUpdate(poll_id, _)
+            | AppEvent::StationStatusUpdate(poll_id, _)
+            | AppEvent::StopStatusError(poll_id, _)
+            | AppEvent::NoLiveData(poll_id, _)
+                if poll_id != self.poll_id || !self.refreshing =>
+            {
+                self.log(format!("Discarding result of superseded poll {}", poll_id));
+            }
+            AppEvent::StopStatusUpdate(_, status) => {
+                self.finish_poll();
+                self.poll_failures = 0;
+                self.last_poll_error = None;
                 if matches!(self.state, AppState::Polling { .. }) {
                     self.log(format!(
                         "Updated stop status: {} train arrivals",
                         status.train_arrivals.len()
                     ));
+                    self.record_status(vec![status.clone()]);
                     self.current_stop_status = Some(status);
                     self.last_update = Some(Instant::now());
                 }
             }
+            AppEvent::StationStatusUpdate(_, station) => {
+                self.finish_poll();
+                self.poll_failures = 0;
+                self.last_poll_error = None;
+                if matches!(self.state, AppState::Polling { .. }) {
+                    self.log(format!(
+                        "Updated station status: {} platforms",
+                        station.platforms.len()
+                    ));
+                    self.record_status(station.platforms.clone());
+                    self.current_station_status = Some(station);
+                    self.last_update = Some(Instant::now());
+                }
+            }
+            AppEvent::NoLiveData(_, reason) => {
+                // Retrying can't help, so the board explains instead of polling again
+                self.finish_poll();
+                self.log(format!("Not polling: {}", reason));
+                self.no_live_data = Some(reason);
+            }
+            AppEvent::StopStatusError(_, error) => {
+                // Keep showing the last good board while retrying with backoff
+                self.finish_poll();
+                self.poll_failures += 1;
+                self.log(format!(
+                    "Poll failed ({} in a row), retrying in {}s: {}",
+                    self.poll_failures,
+                    self.next_poll_delay().as_secs(),
+                    error
+                ));
+                self.last_poll_error = Some(error);
+            }
             AppEvent::LogFileLoaded => {
                 // Log file has been loaded, UI will automatically update
             }
         }
     }
 
-    fn filter_stops(&mut self) {
-        let search_lower = self.search_input.to_lowercase();
+    /// Lists stations or platforms, depending on `station_view`
+    fn show_stop_list(&mut self) {
+        self.stops = if self.station_view {
+            self.station_stops.clone()
+        } else {
+            self.platform_stops.clone()
+        };
+        self.filter_stops();
+    }
 
-        self.filtered_stops = self
-            .stops
+    /// Looks up a stop's display name in either list, for opening a stop by ID
+    fn find_stop(&self, stop_id: &str) -> Option<(String, String)> {
+        self.platform_stops
             .iter()
-            .enumerate()
-            .filter(|(_, (stop_id, display_name))| {
-                stop_id.to_lowercase().contains(&search_lower)
-                    || display_name.to_lowercase().contains(&search_lower)
-            })
-            .map(|(i, _)| i)
-            .collect();
+            .chain(&self.station_stops)
+            .find(|(id, _)| id == stop_id)
+            .cloned()
+    }
 
-        // Reset selection if current selection is no longer valid
-        if let Some(selected) = self.list_state.selected() {
-            if selected >= self.filtered_stops.len() {
-                self.list_state.select(if self.filtered_stops.is_empty() {
-                    None
-                } else {
-                    Some(0)
-                });
-            }
-        } else if !self.filtered_stops.is_empty() {
-            self.list_state.select(Some(0));
+    fn filter_stops(&mut self) {
+        // A query naming a line, e.g. "Q", lists the stops it serves instead of matching names
+        self.filtered_stops = match route_query(&self.search_input, &self.routes) {
+            Some(route_id) => filter_stops_by_route(&self.stops, &self.stop_routes, route_id),
+            None => filter_stops_impl(&self.stops, &self.search_input),
+        };
+        if self.favorites_only {
+            self.filtered_stops
+                .retain(|&i| self.favorites.contains(&self.stops[i].0));
+        }
+        if let Some(line) = &self.line_filter {
+            self.filtered_stops
+                .retain(|&i| line.stop_order.contains_key(&self.stops[i].0));
+            self.filtered_stops
+                .sort_by_key(|&i| line.stop_order[&self.stops[i].0]);
         }
+
+        let selected = clamp_selection(self.list_state.selected(), self.filtered_stops.len());
+        self.list_state.select(selected);
     }
 
     fn should_poll(&self) -> bool {
         match &self.state {
-            AppState::Polling { .. } => self
-                .last_update
-                .map(|last| last.elapsed() >= self.polling_interval)
-                .unwrap_or(true),
+            AppState::Polling { .. } => {
+                !self.refreshing
+                    && self.no_live_data.is_none()
+                    && self.time_until_next_poll().is_zero()
+            }
             _ => false,
         }
     }
 
+    /// Fetches the monitored stop's status on a background task, which reports back
+    /// through `tx` unless the app shuts down first
+    fn spawn_poll(&mut self, tx: &mpsc::UnboundedSender<AppEvent>) {
+        let (Some(checker), Some(stop_id)) = (
+            self.train_checker.clone(),
+            self.get_current_stop_id().map(str::to_string),
+        ) else {
+            return;
+        };
+
+        self.refreshing = true;
+        self.poll_id += 1;
+        let poll_id = self.poll_id;
+        // Cancelled on quit along with everything else, or on its own by `cancel_poll`
+        let cancel = self.shutdown.child_token();
+        self.poll_cancel = Some(cancel.clone());
+        let min_lead_seconds = self.walk_time.as_secs() as i32;
+        let tx = tx.clone();
+        // A station with platforms is polled in every direction at once
+        let is_station = checker.get_child_stops(&stop_id) != [stop_id.as_str()];
+        tokio::spawn(async move {
+            let poll = async {
+                if is_station {
+                    checker
+                        .get_station_status(&stop_id, min_lead_seconds)
+                        .await
+                        .map(|station| AppEvent::StationStatusUpdate(poll_id, station))
+                } else {
+                    checker
+                        .get_stop_status_with_lead(&stop_id, min_lead_seconds)
+                        .await
+                        .map(|status| AppEvent::StopStatusUpdate(poll_id, status))
+                }
+            };
+            let event = tokio::select! {
+                _ = cancel.cancelled() => return,
+                result = poll => result.unwrap_or_else(|e| {
+                    match e.downcast_ref::<TrainCheckerError>() {
+                        Some(no_feeds @ TrainCheckerError::NoFeedsForStop { .. }) => {
+                            AppEvent::NoLiveData(poll_id, no_feeds.to_string())
+                        }
+                        None => AppEvent::StopStatusError(poll_id, e.to_string()),
+                    }
+                }),
+            };
+            // A closed channel means the app is quitting, so the result isn't needed
+            let _ = tx.send(event);
+        });
+    }
+
+    /// Abandons the in-flight poll, if any. A result it already sent is discarded when
+    /// received, since `refreshing` is cleared and the next poll gets a new ID.
+    fn cancel_poll(&mut self) {
+        if let Some(cancel) = self.poll_cancel.take() {
+            cancel.cancel();
+        }
+        self.refreshing = false;
+    }
+
+    /// Records that the in-flight poll completed, successfully or not
+    fn finish_poll(&mut self) {
+        self.refreshing = false;
+        self.last_poll_attempt = Some(Instant::now());
+    }
+
+    /// Delay between a poll and the next one. After failures this backs off exponentially
+    /// from `RETRY_BASE_DELAY`, up to the slowest allowed polling interval.
+    fn next_poll_delay(&self) -> Duration {
+        if self.poll_failures == 0 {
+            return self.polling_interval;
+        }
+        let exponent = (self.poll_failures - 1).min(16);
+        (RETRY_BASE_DELAY * 2u32.pow(exponent)).min(self.config.max_interval)
+    }
+
+    fn time_until_next_poll(&self) -> Duration {
+        self.last_poll_attempt.map_or(Duration::ZERO, |last| {
+            self.next_poll_delay().saturating_sub(last.elapsed())
+        })
+    }
+
     fn get_current_stop_id(&self) -> Option<&str> {
         match &self.state {
             AppState::Polling { stop_id, .. } => Some(stop_id),
@@ -290,15 +1143,34 @@ impl App {
 
         // Spawn TrainChecker initialization
         let init_tx = tx.clone();
+        let offline = self.offline;
+        let config = self.config.clone();
+        let shutdown = self.shutdown.clone();
         tokio::spawn(async move {
-            match TrainChecker::new().await {
+            let progress_tx = init_tx.clone();
+            let report_progress = move |progress| {
+                // Best effort, a closed channel means the app quit
+                let _ = progress_tx.send(AppEvent::GtfsProgress(progress));
+            };
+            let result = tokio::select! {
+                // Quitting while the schedule is still downloading abandons the download
+                _ = shutdown.cancelled() => return,
+                result = create_checker(offline, &config, &report_progress) => result,
+            };
+            match result {
                 Ok(checker) => {
-                    if let Err(_) = init_tx.send(AppEvent::TrainCheckerReady(checker)) {
+                    if init_tx
+                        .send(AppEvent::TrainCheckerReady(Box::new(checker)))
+                        .is_err()
+                    {
                         // Channel closed, app probably quit
                     }
                 }
                 Err(e) => {
-                    if let Err(_) = init_tx.send(AppEvent::TrainCheckerError(e.to_string())) {
+                    if init_tx
+                        .send(AppEvent::TrainCheckerError(e.to_string()))
+                        .is_err()
+                    {
                         // Channel closed, app probably quit
                     }
                 }
@@ -316,6 +1188,7 @@ impl App {
                     Event::Key(key) if key.kind == KeyEventKind::Press => {
                         self.handle_key_event(key);
                     }
+                    Event::Mouse(mouse) => self.handle_mouse_event(mouse),
                     Event::Resize(_, _) => {
                         // Ratatui handles this automatically
                     }
@@ -332,30 +1205,38 @@ impl App {
                 Err(_) => {}       // Timeout, continue
             }
 
-            // Handle polling
-            if self.should_poll() {
-                if let (Some(checker), Some(stop_id)) =
-                    (&self.train_checker, self.get_current_stop_id())
-                {
-                    let stop_id = stop_id.to_string();
-                    match checker.get_stop_status(&stop_id).await {
-                        Ok(status) => {
-                            self.handle_app_event(AppEvent::StopStatusUpdate(status));
-                        }
-                        Err(_) => {} // Silently ignore polling errors
+            // Keep a connection to the feeds open while browsing so the first poll is fast
+            if matches!(self.state, AppState::Selection | AppState::RouteSelection)
+                && self
+                    .last_warm_up
+                    .is_none_or(|last| last.elapsed() >= WARM_UP_INTERVAL)
+                && let Some(checker) = &self.train_checker
+            {
+                let shutdown = self.shutdown.clone();
+                let warm_up = checker.warm_up();
+                tokio::spawn(async move {
+                    tokio::select! {
+                        _ = shutdown.cancelled() => {}
+                        _ = warm_up => {}
                     }
-                }
+                });
+                self.last_warm_up = Some(Instant::now());
+            }
+
+            // Handle polling. The result arrives later as an AppEvent, so input stays responsive
+            if self.should_poll() {
+                self.spawn_poll(&tx);
             }
 
             // Handle log file loading
             if self.needs_log_reload {
                 self.needs_log_reload = false;
-                if let Err(_) = self.update_file_logs().await {
+                if self.update_file_logs().await.is_err() {
                     // Error already logged in update_file_logs
                     self.log("Error reloading log file".to_string());
                 }
                 // Send event to trigger UI update (though it's not strictly necessary)
-                if let Err(_) = tx.send(AppEvent::LogFileLoaded) {
+                if tx.send(AppEvent::LogFileLoaded).is_err() {
                     // Channel closed, app probably quitting
                     self.log("Channel closed, app probably quitting".to_string());
                 }
@@ -366,16 +1247,29 @@ impl App {
             }
         }
 
+        // Stop the init, poll and warm-up tasks so nothing outlives the terminal session
+        self.shutdown.cancel();
         Ok(())
     }
 
     fn draw(&mut self, f: &mut Frame) {
+        let area = f.area();
+        if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+            render_too_small(f);
+            return;
+        }
+
         match &self.state {
             AppState::Loading => render_loading(f, self),
             AppState::Log => render_log(f, self),
+            AppState::RouteSelection => render_route_selection(f, self),
             AppState::Selection => render_selection(f, self),
             AppState::Polling { stop_name, .. } => render_polling(f, self, stop_name),
         }
+
+        if self.show_help {
+            render_help(f, self);
+        }
     }
 }
 
@@ -387,6 +1281,19 @@ fn center(area: Rect, horizontal: Constraint, vertical: Constraint) -> Rect {
     area
 }
 
+fn render_too_small(f: &mut Frame) {
+    let message = Paragraph::new(format!(
+        "Terminal too small (need at least {}×{})",
+        MIN_WIDTH, MIN_HEIGHT
+    ))
+    .style(Style::default().fg(Color::Yellow))
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true });
+
+    let area = center(f.area(), Constraint::Percentage(100), Constraint::Length(3));
+    f.render_widget(message, area);
+}
+
 fn render_loading(f: &mut Frame, app: &App) {
     if let Some(error) = &app.error_message {
         // Show error in a traditional text block
@@ -400,7 +1307,7 @@ fn render_loading(f: &mut Frame, app: &App) {
             Line::from(""),
             Line::from(error.as_str()).style(Style::default().fg(Color::Red)),
             Line::from(""),
-            Line::from("Press 'Ctrl-C' to quit"),
+            Line::from("Press 'q' or 'Ctrl-C' to quit"),
         ]);
 
         let error_paragraph = Paragraph::new(error_text)
@@ -426,7 +1333,8 @@ fn render_loading(f: &mut Frame, app: &App) {
             .build();
 
         // Subtitle text
-        let subtitle = Paragraph::new("NYC Train Checker - Fetching GTFS data...")
+        let subtitle_text = loading_subtitle(app.offline, app.gtfs_progress);
+        let subtitle = Paragraph::new(subtitle_text)
             .style(Style::default().fg(Color::Gray))
             .block(Block::default().borders(Borders::NONE))
             .alignment(Alignment::Center);
@@ -453,69 +1361,190 @@ fn render_loading(f: &mut Frame, app: &App) {
     }
 }
 
-fn render_selection(f: &mut Frame, app: &mut App) {
+fn render_route_selection(f: &mut Frame, app: &mut App) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3), // Header
-            Constraint::Length(3), // Search
             Constraint::Min(0),    // List
             Constraint::Length(3), // Footer
         ])
         .split(f.area());
 
-    // Header
-    let header = Paragraph::new("NYC Train Checker - Select a Stop")
+    let header = Paragraph::new("NYC Train Checker - Select a Line")
         .block(Block::default().borders(Borders::ALL))
         .style(Style::default().fg(Color::Blue));
     f.render_widget(header, chunks[0]);
 
-    // Search input
-    let search_title = if app.search_input.is_empty() {
-        "Search (type to filter)"
-    } else {
-        "Search (typing...)"
-    };
-    let search_block = Block::default().title(search_title).borders(Borders::ALL);
-    let search_text = if app.search_input.is_empty() {
-        "Start typing to search..."
-    } else {
-        &app.search_input
-    };
-    let search_paragraph = Paragraph::new(search_text)
-        .block(search_block)
-        .style(Style::default().fg(Color::Yellow));
-    f.render_widget(search_paragraph, chunks[1]);
-
-    // Stop list
+    // One row per line, with a bullet in the line's color
     let items: Vec<ListItem> = app
-        .filtered_stops
+        .routes
         .iter()
-        .map(|&i| {
-            let (_stop_id, display_name) = &app.stops[i];
-            ListItem::new(display_name.as_str())
+        .map(|route| {
+            let (r, g, b) = route.color;
+            ListItem::new(Line::from(vec![
+                Span::styled("● ", Style::default().fg(Color::Rgb(r, g, b))),
+                Span::raw(format!(
+                    "{} — {}",
+                    route.short_name.as_deref().unwrap_or(&route.route_id),
+                    route.long_name.as_deref().unwrap_or("")
+                )),
+            ]))
         })
         .collect();
-
     let list = List::new(items)
         .block(
             Block::default()
-                .title(format!(
-                    "Stops ({}/{})",
-                    app.filtered_stops.len(),
-                    app.stops.len()
-                ))
+                .title(format!("Lines ({})", app.routes.len()))
                 .borders(Borders::ALL),
         )
         .style(Style::default().fg(Color::White))
         .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
         .highlight_symbol(">> ");
+    f.render_stateful_widget(list, chunks[1], &mut app.route_list_state);
+
+    let footer = Paragraph::new(
+        "↑↓/jk: Navigate | Enter: List stops | Esc/Shift-Tab: All stops | ?: Help | q: Quit",
+    )
+    .block(Block::default().borders(Borders::ALL))
+    .style(Style::default().fg(Color::Gray));
+    f.render_widget(footer, chunks[2]);
+}
 
-    f.render_stateful_widget(list, chunks[2], &mut app.list_state);
-
-    // Footer with instructions
-    let footer = Paragraph::new("↑↓: Navigate | Enter: Select | Ctrl-C: Quit")
-        .block(Block::default().borders(Borders::ALL))
+fn render_selection(f: &mut Frame, app: &mut App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Length(3), // Search
+            Constraint::Min(0),    // List
+            Constraint::Length(3), // Footer
+        ])
+        .split(f.area());
+
+    // Header
+    let schedule = app
+        .train_checker
+        .as_ref()
+        .and_then(|checker| checker.gtfs_info().schedule_label());
+    let header_text = match schedule {
+        Some(schedule) => format!("NYC Train Checker - Select a Stop | Schedule: {}", schedule),
+        None => "NYC Train Checker - Select a Stop".to_string(),
+    };
+    let header = Paragraph::new(header_text)
+        .block(Block::default().borders(Borders::ALL))
+        .style(Style::default().fg(Color::Blue));
+    f.render_widget(header, chunks[0]);
+
+    // Search input
+    let search_title = if app.navigation_mode {
+        "Search (navigating - Esc or / to type)"
+    } else if app.search_input.is_empty() {
+        "Search (type to filter)"
+    } else {
+        "Search (typing...)"
+    };
+    let search_block = Block::default().title(search_title).borders(Borders::ALL);
+    let search_text = if app.search_input.is_empty() {
+        "Start typing to search..."
+    } else {
+        &app.search_input
+    };
+    let search_paragraph = Paragraph::new(search_text)
+        .block(search_block)
+        .style(Style::default().fg(Color::Yellow));
+    f.render_widget(search_paragraph, chunks[1]);
+
+    // Stop list
+    let items: Vec<ListItem> = app
+        .filtered_stops
+        .iter()
+        .map(|&i| {
+            let (stop_id, display_name) = &app.stops[i];
+            let mut label = display_name.clone();
+            if app.favorites.contains(stop_id) {
+                label = format!("★ {}", label);
+            }
+            let accessible = app.train_checker.as_ref().is_some_and(|checker| {
+                checker.stop_accessibility(stop_id) == Accessibility::Accessible
+            });
+            if accessible {
+                label.push_str(" ♿");
+            }
+            ListItem::new(label)
+        })
+        .collect();
+
+    let list_title = if app.favorites_only {
+        format!(
+            "Favorites ({}/{})",
+            app.filtered_stops.len(),
+            app.favorites.len()
+        )
+    } else if let Some(line) = &app.line_filter {
+        format!(
+            "Stops on the {} line ({})",
+            line.label,
+            app.filtered_stops.len()
+        )
+    } else if let Some(route_id) = route_query(&app.search_input, &app.routes) {
+        format!(
+            "Stops served by the {} ({}/{})",
+            route_id,
+            app.filtered_stops.len(),
+            app.stops.len()
+        )
+    } else if app.station_view {
+        format!(
+            "Stations ({}/{})",
+            app.filtered_stops.len(),
+            app.stops.len()
+        )
+    } else {
+        format!(
+            "Platforms ({}/{})",
+            app.filtered_stops.len(),
+            app.stops.len()
+        )
+    };
+    let list_block = Block::default().title(list_title).borders(Borders::ALL);
+
+    if app.filtered_stops.is_empty() {
+        // Explain the dead end instead of showing an empty list
+        let message = if !app.search_input.is_empty() {
+            format!(
+                "No stops match '{}' — press Backspace to edit",
+                app.search_input
+            )
+        } else if app.favorites_only {
+            "No favorites yet — press Tab for all stops, then f in navigation mode to add one"
+                .to_string()
+        } else {
+            "No stops available".to_string()
+        };
+        let no_matches = Paragraph::new(message)
+            .block(list_block)
+            .style(Style::default().fg(Color::Yellow));
+        f.render_widget(no_matches, chunks[2]);
+    } else {
+        let list = List::new(items)
+            .block(list_block)
+            .style(Style::default().fg(Color::White))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol(">> ");
+
+        f.render_stateful_widget(list, chunks[2], &mut app.list_state);
+        app.stop_list_area = chunks[2];
+    }
+
+    // Footer with instructions
+    let footer_text = if app.navigation_mode {
+        "↑↓/jk: Navigate | Enter: Select | f: Favorite | Tab: Favorites | Shift-Tab: By line | Esc: Search | ?: Help | q: Quit"
+    } else {
+        "↑↓: Navigate | Enter: Select | Tab: Favorites | Shift-Tab: By line | Esc: Navigation mode (q: Quit) | ?: Help | Ctrl-C: Quit"
+    };
+    let footer = Paragraph::new(footer_text)
+        .block(Block::default().borders(Borders::ALL))
         .style(Style::default().fg(Color::Gray));
     f.render_widget(footer, chunks[3]);
 }
@@ -531,66 +1560,284 @@ fn render_polling(f: &mut Frame, app: &App, stop_name: &str) {
         .split(f.area());
 
     // Header
-    let header_text = format!("Monitoring: {}", stop_name);
+    let is_favorite = app
+        .get_current_stop_id()
+        .is_some_and(|stop_id| app.favorites.contains(stop_id));
+    let mut header_text = if is_favorite {
+        format!("Monitoring: ★ {}", stop_name)
+    } else {
+        format!("Monitoring: {}", stop_name)
+    };
+    if let Some(routes) = app.route_filter_label() {
+        header_text.push_str(&format!(" [{} only]", routes));
+    }
+    if app.offline {
+        header_text.push_str(" [OFFLINE - SIMULATED ARRIVALS]");
+    }
     let header = Paragraph::new(header_text)
         .block(Block::default().borders(Borders::ALL))
         .style(Style::default().fg(Color::Green));
     f.render_widget(header, chunks[0]);
 
-    // Train arrivals (full width)
-    render_train_arrivals(f, app, chunks[1]);
+    // Trend sparklines take rows below the board, one per route plus the borders
+    let mut board_area = chunks[1];
+    if app.show_trends {
+        let rows = app
+            .monitored_statuses()
+            .iter()
+            .map(|status| status.train_arrivals.len())
+            .sum::<usize>()
+            .max(1) as u16;
+        let [top, trends_area] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(rows.min(MAX_TREND_ROWS) + 2),
+        ])
+        .areas(board_area);
+        board_area = top;
+        render_trends(f, app, trends_area);
+    }
+
+    // Train arrivals, full width unless the legend takes a column on the right
+    if app.show_legend {
+        let [board, legend_area] =
+            Layout::horizontal([Constraint::Min(0), Constraint::Length(LEGEND_WIDTH)])
+                .areas(board_area);
+        board_area = board;
+        render_legend(f, app, legend_area);
+    }
+    match &app.current_station_status {
+        // A column per direction, like the countdown clocks on the platforms
+        Some(station) => {
+            let columns = Layout::horizontal(station.platforms.iter().map(|_| Constraint::Fill(1)))
+                .split(board_area);
+            for (platform, column) in station.platforms.iter().zip(columns.iter()) {
+                render_train_arrivals(f, app, Some(platform), *column);
+            }
+        }
+        None => render_train_arrivals(f, app, app.current_stop_status.as_ref(), board_area),
+    }
 
     // Bottom bar with status and controls
     render_bottom_bar(f, app, chunks[2]);
 }
 
-fn render_train_arrivals(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+/// Draws a sparkline per route of how far off its soonest train was at each recent poll.
+/// A train approaching shows as falling bars, until it leaves and the next one takes over.
+fn render_trends(f: &mut Frame, app: &App, area: Rect) {
     let block = Block::default()
-        .title("Upcoming Trains")
+        .title(format!(
+            "Soonest train, last {} polls",
+            app.status_history.len()
+        ))
         .borders(Borders::ALL);
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    // A row per route, per platform when monitoring a whole station
+    let statuses = app.monitored_statuses();
+    let routes: Vec<_> = statuses
+        .iter()
+        .flat_map(|status| {
+            status
+                .sorted_routes()
+                .into_iter()
+                .map(move |(route_id, arrivals)| (*status, route_id, arrivals))
+        })
+        .collect();
+    let rows = Layout::vertical(routes.iter().map(|_| Constraint::Length(1))).split(inner_area);
+    for ((status, route_id, arrivals), row) in routes.into_iter().zip(rows.iter()) {
+        let [label_area, sparkline_area] =
+            Layout::horizontal([Constraint::Length(14), Constraint::Min(0)]).areas(*row);
+        let mut label = route_label(route_id, arrivals.first());
+        if statuses.len() > 1 {
+            match status.direction {
+                Some(StopDirection::North) => label.push_str(" ↑"),
+                Some(StopDirection::South) => label.push_str(" ↓"),
+                None => {}
+            }
+        }
+        f.render_widget(Paragraph::new(label), label_area);
+
+        // Newest polls on the right, trimmed to what fits
+        let history = soonest_arrival_history(&app.status_history, &status.stop_id, route_id);
+        let skip = history.len().saturating_sub(sparkline_area.width as usize);
+        let sparkline = Sparkline::default()
+            .data(history.into_iter().skip(skip))
+            .style(Style::default().fg(Color::Cyan));
+        f.render_widget(sparkline, sparkline_area);
+    }
+}
+
+/// Seconds until a route's soonest train at a platform at each poll in the history, oldest
+/// first. None for polls where the route had no trains there.
+fn soonest_arrival_history(
+    history: &VecDeque<Vec<StopStatus>>,
+    stop_id: &str,
+    route_id: &str,
+) -> Vec<Option<u64>> {
+    history
+        .iter()
+        .map(|platforms| {
+            let status = platforms.iter().find(|status| status.stop_id == stop_id)?;
+            let arrivals = status.train_arrivals.get(route_id)?;
+            let soonest = arrivals.iter().map(|arrival| arrival.arrival_time).min()?;
+            Some(soonest.max(0) as u64)
+        })
+        .collect()
+}
+
+/// Lists the lines serving the monitored stop with their bullet colors and full names
+fn render_legend(f: &mut Frame, app: &App, area: Rect) {
+    let served = app.served_routes();
+    let routes: Vec<RouteInfo> = match &app.train_checker {
+        Some(checker) => checker
+            .get_all_routes()
+            .into_iter()
+            .filter(|route| served.contains(route.route_id.as_str()))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let lines: Vec<Line> = routes
+        .iter()
+        .flat_map(|route| {
+            let (r, g, b) = route.color;
+            let name = route.short_name.as_deref().unwrap_or(&route.route_id);
+            [
+                Line::from(vec![
+                    Span::styled("● ", Style::default().fg(Color::Rgb(r, g, b))),
+                    Span::styled(
+                        name.to_string(),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                ]),
+                Line::styled(
+                    format!("  {}", route.long_name.as_deref().unwrap_or("")),
+                    Style::default().fg(Color::Gray),
+                ),
+            ]
+        })
+        .collect();
+
+    let legend = Paragraph::new(lines)
+        .block(Block::default().title("Lines").borders(Borders::ALL))
+        .wrap(Wrap { trim: false });
+    f.render_widget(legend, area);
+}
+
+fn render_train_arrivals(
+    f: &mut Frame,
+    app: &App,
+    status: Option<&StopStatus>,
+    area: ratatui::layout::Rect,
+) {
+    // Label the board with its mode and direction of travel, e.g. "Arrivals — Downtown"
+    let board = if app.show_departures {
+        "Departures"
+    } else {
+        "Arrivals"
+    };
+    let mut title = match status.and_then(|s| s.direction) {
+        Some(direction) => format!("{} — {}", board, direction.label()),
+        None => board.to_string(),
+    };
+    if let Some(status) = status.filter(|s| s.is_partial()) {
+        title.push_str(&format!(
+            " ({}/{} feeds)",
+            status.feeds_succeeded, status.feeds_total
+        ));
+    }
+    let block = Block::default().title(title.clone()).borders(Borders::ALL);
+
+    if let Some(status) = status {
+        // Routes in the schedule without upcoming trains are listed after the live ones
+        let idle_routes = if app.hide_idle_routes {
+            Vec::new()
+        } else {
+            status.idle_routes()
+        };
+        let routes: Vec<(&str, &[TrainArrival])> = status
+            .sorted_routes()
+            .into_iter()
+            .map(|(route_id, arrivals)| (route_id, arrivals.as_slice()))
+            .chain(idle_routes.into_iter().map(|route_id| (route_id, &[][..])))
+            .filter(|(route_id, _)| {
+                app.route_filter
+                    .as_ref()
+                    .is_none_or(|filter| filter.contains(*route_id))
+            })
+            .collect();
 
-    if let Some(status) = &app.current_stop_status {
-        if status.train_arrivals.is_empty() {
-            let no_trains = Paragraph::new("No upcoming trains found")
+        if routes.is_empty() {
+            let (message, color) = empty_board_message(
+                Some(status),
+                app.last_poll_error.is_some(),
+                app.route_filter_label(),
+            );
+            let no_trains = Paragraph::new(message)
                 .block(block)
-                .style(Style::default().fg(Color::Yellow));
+                .style(Style::default().fg(color));
             f.render_widget(no_trains, area);
+        } else if app.show_gauges {
+            render_arrival_gauges(f, app, &routes, block, area);
         } else {
             // Format text lines for BigText display
             let mut big_text_lines = Vec::new();
 
-            // Sort train arrivals alphabetically by route_id for deterministic order
-            let mut sorted_arrivals: Vec<_> = status.train_arrivals.iter().collect();
-            sorted_arrivals.sort_by_key(|(route_id, _)| *route_id);
+            let now = chrono::Utc::now();
+            for (route_id, arrivals) in routes {
+                let route_display = route_label(route_id, arrivals.first());
 
-            for (route_id, arrivals) in sorted_arrivals {
-                let route_display = arrivals
+                // Count down from the absolute time so the board ticks between polls.
+                // Trains without a departure time fall back to their arrival.
+                let soonest = arrivals
                     .first()
-                    .and_then(|a| a.route_name.as_ref())
-                    .unwrap_or(route_id);
-
-                // Format as "G: in 18 and 30 minutes"
-                let arrival_times: Vec<String> = arrivals
+                    .map(|arrival| (arrival, board_seconds_until(app, arrival, now)));
+                let later: String = arrivals
                     .iter()
-                    .take(2)
-                    .map(|arrival| arrival.human_time.clone())
+                    .take(app.arrivals_shown)
+                    .skip(1)
+                    .map(|arrival| {
+                        format!(
+                            ", {}",
+                            board_countdown(app, arrival, board_seconds_until(app, arrival, now))
+                        )
+                    })
                     .collect();
 
-                let formatted_line = if arrival_times.len() == 1 {
-                    format!("{}: {}", route_display, arrival_times[0])
-                } else if arrival_times.len() == 2 {
-                    format!("{}: {}", route_display, arrival_times[0])
-                } else {
-                    format!("{}: No arrivals", route_display)
+                // Trains about to pull in are called out so they stand out from the rest
+                let approaching = soonest.is_some_and(|(_, seconds)| {
+                    seconds < app.config.approaching_threshold.as_secs() as i64
+                });
+                let line = match soonest {
+                    Some(_) if approaching => Line::styled(
+                        format!("{}: Now{}", route_display, later),
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Some((arrival, seconds)) => Line::from(format!(
+                        "{}: {}{}",
+                        route_display,
+                        board_countdown(app, arrival, seconds),
+                        later
+                    )),
+                    None => Line::styled(
+                        format!("{}: No service", route_display),
+                        Style::default().fg(Color::DarkGray),
+                    ),
                 };
 
-                big_text_lines.push(formatted_line);
+                big_text_lines.push((approaching, line));
             }
 
+            // Approaching trains go to the top, otherwise keep the route order
+            big_text_lines.sort_by_key(|(approaching, _)| !approaching);
+
             // Create a content area inside the border
             let inner_area = Block::default()
                 .borders(Borders::ALL)
-                .title("Upcoming Trains")
+                .title(title)
                 .padding(Padding::new(1, 1, 1, 1))
                 .inner(area);
 
@@ -599,7 +1846,7 @@ fn render_train_arrivals(f: &mut Frame, app: &App, area: ratatui::layout::Rect)
 
             // Create BigText with smaller pixel size
             let big_text_lines: Vec<Line> =
-                big_text_lines.into_iter().map(|s| Line::from(s)).collect();
+                big_text_lines.into_iter().map(|(_, line)| line).collect();
 
             let big_text = BigText::builder()
                 .pixel_size(PixelSize::Quadrant)
@@ -618,20 +1865,246 @@ fn render_train_arrivals(f: &mut Frame, app: &App, area: ratatui::layout::Rect)
 
             f.render_widget(big_text, centered_area);
         }
+    } else if let Some(reason) = &app.no_live_data {
+        let text = vec![
+            Line::from("We don't know how to get live data for this stop"),
+            Line::styled(reason.as_str(), Style::default().fg(Color::Gray)),
+            Line::from("Press s to pick another stop"),
+        ];
+        let unavailable = Paragraph::new(text)
+            .block(block)
+            .style(Style::default().fg(Color::Yellow))
+            .wrap(Wrap { trim: false });
+        f.render_widget(unavailable, area);
     } else {
-        let loading = Paragraph::new("Loading train data...")
+        let (message, color) = empty_board_message(None, app.last_poll_error.is_some(), None);
+        let empty = Paragraph::new(message)
             .block(block)
-            .style(Style::default().fg(Color::Yellow));
-        f.render_widget(loading, area);
+            .style(Style::default().fg(color));
+        f.render_widget(empty, area);
+    }
+}
+
+/// Explains a board with no trains on it, telling apart live data that has no trains,
+/// feeds that are down, and a first poll that hasn't finished yet, so an outage doesn't
+/// look like the subway stopped running. `poll_failed` is whether the latest poll failed.
+fn empty_board_message(
+    status: Option<&StopStatus>,
+    poll_failed: bool,
+    route_filter: Option<String>,
+) -> (String, Color) {
+    let unavailable = || {
+        (
+            "Realtime data unavailable — retrying".to_string(),
+            Color::Red,
+        )
+    };
+    let Some(status) = status else {
+        return if poll_failed {
+            unavailable()
+        } else {
+            ("Loading train data...".to_string(), Color::Yellow)
+        };
+    };
+    // A status left over from before the latest poll failed says nothing about the trains
+    // running now
+    if poll_failed {
+        return unavailable();
+    }
+
+    let mut message = match route_filter {
+        Some(filter) => format!("No upcoming {} trains — press o to change", filter),
+        // Offline boards are simulated, so there's no live data to vouch for
+        None if status.simulated => {
+            "No simulated trains — offline, live data isn't fetched".to_string()
+        }
+        None => "No upcoming trains — live data is up to date".to_string(),
+    };
+    // Missing feeds may be why the board is empty
+    if status.is_partial() {
+        message.push_str(&format!(
+            " ({} of {} feeds unavailable)",
+            status.feeds_total - status.feeds_succeeded,
+            status.feeds_total
+        ));
+    }
+    (message, Color::Yellow)
+}
+
+/// Seconds until a train reaches the platform, or leaves it when showing departures.
+/// Trains without a departure time fall back to their arrival.
+fn board_seconds_until(
+    app: &App,
+    arrival: &TrainArrival,
+    now: chrono::DateTime<chrono::Utc>,
+) -> i64 {
+    let seconds = if app.show_departures {
+        arrival.departure_seconds_until(now)
+    } else {
+        None
+    };
+    seconds.unwrap_or_else(|| arrival.seconds_until(now))
+}
+
+/// A train's countdown on the board, followed by its local clock time when that's toggled
+/// on, e.g. "3:05 (3:42 PM)". The clock time was fixed when the train was fetched, so it
+/// stays put while the countdown ticks.
+fn board_countdown(app: &App, arrival: &TrainArrival, seconds: i64) -> String {
+    let countdown = format_arrival_countdown(arrival, seconds);
+    if !app.show_clock {
+        return countdown;
+    }
+    let time = if app.show_departures {
+        arrival.departure_local
+    } else {
+        None
+    };
+    format!(
+        "{} ({})",
+        countdown,
+        format_clock_time(time.unwrap_or(arrival.arrival_local))
+    )
+}
+
+/// Formats a local time the way station clocks read, e.g. "3:42 PM"
+fn format_clock_time(time: chrono::DateTime<chrono_tz::Tz>) -> String {
+    time.format("%-I:%M %p").to_string()
+}
+
+/// How full a train's countdown gauge is: empty at `GAUGE_HORIZON` or further out, full
+/// once it's due
+fn gauge_ratio(seconds: i64) -> f64 {
+    let horizon = GAUGE_HORIZON.as_secs() as f64;
+    (1.0 - seconds as f64 / horizon).clamp(0.0, 1.0)
+}
+
+/// Draws one gauge row per upcoming train, soonest first
+fn render_arrival_gauges(
+    f: &mut Frame,
+    app: &App,
+    routes: &[(&str, &[TrainArrival])],
+    block: Block,
+    area: Rect,
+) {
+    let now = chrono::Utc::now();
+    let mut trains: Vec<(String, &TrainArrival, i64)> = routes
+        .iter()
+        .flat_map(|(route_id, arrivals)| {
+            let label = route_label(route_id, arrivals.first());
+            arrivals
+                .iter()
+                .take(app.arrivals_shown)
+                .map(move |arrival| {
+                    (
+                        label.clone(),
+                        arrival,
+                        board_seconds_until(app, arrival, now),
+                    )
+                })
+        })
+        .collect();
+    trains.sort_by_key(|(_, _, seconds)| *seconds);
+
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    // Each gauge is a single row with a blank row between them
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(trains.iter().map(|_| Constraint::Length(2)))
+        .split(inner_area);
+    for ((label, arrival, seconds), row) in trains.into_iter().zip(rows.iter()) {
+        let approaching = seconds < app.config.approaching_threshold.as_secs() as i64;
+        let color = if approaching {
+            Color::Yellow
+        } else {
+            Color::Cyan
+        };
+        let gauge = Gauge::default()
+            .gauge_style(Style::default().fg(color).bg(Color::Black))
+            .ratio(gauge_ratio(seconds))
+            .label(format!(
+                "{}  {}",
+                label,
+                board_countdown(app, arrival, seconds)
+            ));
+        let [row, _] = Layout::vertical([Constraint::Length(1), Constraint::Length(1)]).areas(*row);
+        f.render_widget(gauge, row);
+    }
+}
+
+/// Names a route for display from its first arrival, marking express trains, e.g.
+/// "6 ⟩ Express"
+fn route_label(route_id: &str, first: Option<&TrainArrival>) -> String {
+    let name = first
+        .and_then(|a| a.route_name.as_deref())
+        .unwrap_or(route_id);
+    if first.is_some_and(|a| a.is_express) {
+        format!("{} ⟩ Express", name.trim_end_matches('X'))
+    } else {
+        name.to_string()
+    }
+}
+
+/// Describes what the loading screen is waiting on, with how much of the schedule has
+/// downloaded once that's known
+fn loading_subtitle(offline: bool, progress: Option<DownloadProgress>) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    if offline {
+        return "NYC Train Checker - Loading cached GTFS data (offline)...".to_string();
+    }
+    let Some(progress) = progress else {
+        return "NYC Train Checker - Fetching GTFS data...".to_string();
+    };
+    let downloaded = progress.downloaded as f64 / MB;
+    match (progress.percent(), progress.total) {
+        (Some(percent), Some(total)) => format!(
+            "NYC Train Checker - Fetching GTFS data... {}% ({:.1} of {:.1} MB)",
+            percent,
+            downloaded,
+            total as f64 / MB
+        ),
+        // Without a length the growing byte count shows the download is still alive
+        _ => format!(
+            "NYC Train Checker - Fetching GTFS data... {:.1} MB",
+            downloaded
+        ),
+    }
+}
+
+/// Formats a countdown as "m:ss", or "Due" once the train should be at the platform
+fn format_countdown(seconds: i64) -> String {
+    if seconds <= 0 {
+        "Due".to_string()
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+/// Like `format_countdown`, prefixed with "~" when the train's time is only an estimate
+fn format_arrival_countdown(arrival: &TrainArrival, seconds: i64) -> String {
+    if arrival.approximate {
+        format!("~{}", format_countdown(seconds))
+    } else {
+        format_countdown(seconds)
     }
 }
 
 fn render_bottom_bar(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    // Create the footer text with current rate
-    let footer_text = format!(
-        "Rate: {}s | s: Switch Stop | +/-: Adjust Rate | Ctrl-C: Quit",
-        app.polling_interval.as_secs()
-    );
+    // Create the footer text with current rate, unless a confirmation is being flashed or
+    // polls are failing
+    let (footer_text, footer_color) = match (app.active_flash(), &app.last_poll_error) {
+        (Some(message), _) => (message.to_string(), Color::Gray),
+        (None, Some(error)) => (format!("Poll failed: {}", error), Color::Red),
+        (None, None) => (
+            format!(
+                "Rate: {}s | Walk: {}s | s: Switch Stop | r: Refresh | f: Favorite | ?: Help | q: Quit",
+                app.polling_interval.as_secs(),
+                app.walk_time.as_secs()
+            ),
+            Color::Gray,
+        ),
+    };
 
     // Create status text
     let mut status_text = String::new();
@@ -642,22 +2115,73 @@ fn render_bottom_bar(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         let status_symbol = match status {
             TrainCheckerStatus::Ok => "OK",
             TrainCheckerStatus::Error => "ERR",
+            TrainCheckerStatus::Throttled => "THROTTLED",
         };
 
+        // Current-cycle health first, then the lifetime failure count
+        status_text = status_symbol.to_string();
+        if let Some(summary) = checker.last_fetch_summary()
+            && summary.requested > 0
+        {
+            status_text.push_str(&format!(" {}/{}", summary.succeeded, summary.requested));
+        }
         if failed_requests > 0 {
-            status_text = format!("{}:{}", status_symbol, failed_requests);
-        } else {
-            status_text = status_symbol.to_string();
+            status_text.push_str(&format!(" ({} failed total)", failed_requests));
         }
 
-        if let Some(last_update) = app.last_update {
+        if app.refreshing {
+            status_text.push_str(" (refreshing…)");
+        } else if app.poll_failures > 0 {
+            // Make it clear the app is still trying after a failed poll
+            status_text.push_str(&format!(
+                " (poll failed, retrying in {}s)",
+                app.time_until_next_poll().as_secs()
+            ));
+        } else if let Some(last_update) = app.last_update {
             let elapsed = last_update.elapsed().as_secs();
             status_text.push_str(&format!(" ({}s ago)", elapsed));
         }
     }
 
+    let status_color = if let Some(checker) = &app.train_checker {
+        match checker.get_status() {
+            TrainCheckerStatus::Ok => Color::Green,
+            TrainCheckerStatus::Error => Color::Red,
+            TrainCheckerStatus::Throttled => Color::Yellow,
+        }
+    } else {
+        Color::Gray
+    };
+
+    // One colored square per polled feed, showing which lines are affected by failures
+    let mut status_spans = Vec::new();
+    if let Some(checker) = &app.train_checker {
+        let mut feeds: Vec<_> = checker.get_feed_health().into_iter().collect();
+        feeds.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (feed_suffix, health) in feeds {
+            let color = if health.is_healthy() {
+                Color::Green
+            } else {
+                Color::Red
+            };
+            // The numbered lines use the feed without a suffix
+            let label = if feed_suffix.is_empty() {
+                "1-7".to_string()
+            } else {
+                feed_suffix
+            };
+            status_spans.push(Span::styled("■", Style::default().fg(color)));
+            status_spans.push(Span::styled(
+                format!("{} ", label),
+                Style::default().fg(Color::Gray),
+            ));
+        }
+    }
+    status_spans.push(Span::styled(status_text, Style::default().fg(status_color)));
+    let status_line = Line::from(status_spans);
+
     // Calculate layout - status is right-aligned with its content width
-    let status_width = status_text.len() as u16 + 2; // +2 for borders
+    let status_width = status_line.width() as u16 + 2; // +2 for borders
     let footer_width = area.width.saturating_sub(status_width);
 
     let bottom_chunks = Layout::default()
@@ -671,44 +2195,37 @@ fn render_bottom_bar(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     // Render footer
     let footer = Paragraph::new(footer_text)
         .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::Gray));
+        .style(Style::default().fg(footer_color));
     f.render_widget(footer, bottom_chunks[0]);
 
     // Render status (right-aligned)
-    let status_color = if let Some(checker) = &app.train_checker {
-        match checker.get_status() {
-            TrainCheckerStatus::Ok => Color::Green,
-            TrainCheckerStatus::Error => Color::Red,
-        }
-    } else {
-        Color::Gray
-    };
-
-    let status = Paragraph::new(status_text)
-        .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(status_color));
+    let status = Paragraph::new(status_line).block(Block::default().borders(Borders::ALL));
     f.render_widget(status, bottom_chunks[1]);
 }
 
-fn render_log(f: &mut Frame, app: &App) {
+fn render_log(f: &mut Frame, app: &mut App) {
     let main_block = Block::default()
-        .title("Debug Log (from file) (l to enter, l to exit, Ctrl-C to quit)")
+        .title(format!(
+            "Debug Log (from file) [{}] (l to enter, l to exit, ↑↓/PgUp/PgDn to scroll, d to toggle DEBUG, q to quit)",
+            app.log_level
+        ))
         .borders(Borders::ALL)
         .style(Style::default().fg(Color::White));
 
-    let log_messages = if app.file_log_entries.is_empty() {
+    // Visible lines inside the borders, used for page-sized scrolling
+    app.log_page_size = f.area().height.saturating_sub(2).max(1) as usize;
+
+    let mut list_state = ListState::default();
+    let log_messages = if app.log_reader.entries().is_empty() {
         vec![ListItem::new("No log entries found or logs not yet loaded")]
     } else {
-        // Calculate how many lines we can display (subtract 2 for borders)
-        let display_height = f.area().height.saturating_sub(2) as usize;
-        let max_lines = display_height.max(10); // Ensure at least 10 lines
-
-        // Take the last N lines (most recent) and display in chronological order
-        // This way newest logs appear at the bottom
-        let start_index = app.file_log_entries.len().saturating_sub(max_lines);
-        app.file_log_entries
+        // Select the line `log_scroll` entries up from the newest so the viewport follows it.
+        // Newest logs appear at the bottom.
+        let last_index = app.log_reader.entries().len() - 1;
+        list_state.select(Some(last_index.saturating_sub(app.log_scroll)));
+        app.log_reader
+            .entries()
             .iter()
-            .skip(start_index)
             .map(|msg| ListItem::new(msg.clone()))
             .collect::<Vec<_>>()
     };
@@ -718,21 +2235,879 @@ fn render_log(f: &mut Frame, app: &App) {
         .style(Style::default().fg(Color::White))
         .highlight_style(Style::default().bg(Color::Blue));
 
-    f.render_widget(log_list, f.area());
+    f.render_stateful_widget(log_list, f.area(), &mut list_state);
+}
+
+/// The route a search query names, if it's a single route ID like "Q" or "6x"
+fn route_query<'a>(query: &str, routes: &'a [RouteInfo]) -> Option<&'a str> {
+    let query = query.trim();
+    if query.is_empty() || query.contains(char::is_whitespace) {
+        return None;
+    }
+    routes
+        .iter()
+        .find(|route| route.route_id.eq_ignore_ascii_case(query))
+        .map(|route| route.route_id.as_str())
+}
+
+/// Indices of the stops a route serves
+fn filter_stops_by_route(
+    stops: &[(String, String)],
+    stop_routes: &HashMap<String, HashSet<String>>,
+    route_id: &str,
+) -> Vec<usize> {
+    stops
+        .iter()
+        .enumerate()
+        .filter(|(_, (stop_id, _))| {
+            stop_routes
+                .get(stop_id)
+                .is_some_and(|routes| routes.contains(route_id))
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Returns the indices of the stops whose ID or name contains `query`, in their original
+/// order. Names are compared after `normalize_stop_query`, so spelling variants match.
+fn filter_stops_impl(stops: &[(String, String)], query: &str) -> Vec<usize> {
+    let id_query = query.to_lowercase();
+    let name_query = normalize_stop_query(query);
+    stops
+        .iter()
+        .enumerate()
+        .filter(|(_, (stop_id, display_name))| {
+            stop_id.to_lowercase().contains(&id_query)
+                || normalize_stop_query(display_name).contains(&name_query)
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Reduces a stop name or search query to a canonical form: lowercase, punctuation as
+/// spaces, ordinals as plain numbers ("42nd" -> "42") and the abbreviations MTA stop names
+/// use ("Street" -> "st", "Avenue" -> "av", "Square" -> "sq")
+fn normalize_stop_query(s: &str) -> String {
+    let s = s
+        .to_lowercase()
+        .replace(|c: char| !c.is_alphanumeric(), " ");
+    let words: Vec<&str> = s
+        .split_whitespace()
+        .map(|word| {
+            // Ordinals: 1st, 2nd, 3rd, 4th, ...
+            let number = ["st", "nd", "rd", "th"]
+                .iter()
+                .find_map(|suffix| word.strip_suffix(suffix))
+                .filter(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()));
+            if let Some(number) = number {
+                return number;
+            }
+            match word {
+                "street" => "st",
+                "avenue" | "ave" => "av",
+                "square" => "sq",
+                "boulevard" => "blvd",
+                "parkway" => "pkwy",
+                "place" => "pl",
+                "road" => "rd",
+                _ => word,
+            }
+        })
+        .collect();
+    words.join(" ")
+}
+
+/// Picks the stops worth listing: the N/S platforms, since arrivals are reported per
+/// direction, plus any other stop without platforms of its own so no station disappears
+/// from the list entirely
+fn selectable_stops(stops: Vec<(String, Option<String>)>) -> Vec<(String, Option<String>)> {
+    let is_platform = |id: &str| id.ends_with('N') || id.ends_with('S');
+    let ids: HashSet<&str> = stops.iter().map(|(id, _)| id.as_str()).collect();
+    let keep: Vec<bool> = stops
+        .iter()
+        .map(|(id, _)| {
+            is_platform(id)
+                || !(ids.contains(format!("{}N", id).as_str())
+                    || ids.contains(format!("{}S", id).as_str()))
+        })
+        .collect();
+    stops
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(stop, keep)| keep.then_some(stop))
+        .collect()
+}
+
+/// Changes a polling interval by `delta_secs` (negative is faster), keeping the result
+/// within `min..=max` even when `current` started outside it
+fn adjust_interval(current: Duration, delta_secs: i64, min: Duration, max: Duration) -> Duration {
+    let delta = Duration::from_secs(delta_secs.unsigned_abs());
+    let adjusted = if delta_secs < 0 {
+        current.saturating_sub(delta)
+    } else {
+        current.saturating_add(delta)
+    };
+    // A misconfigured minimum above the maximum wins, rather than panicking in clamp
+    adjusted.clamp(min, max.max(min))
+}
+
+/// Keeps a list selection valid for a list of `len` items: an out of range selection
+/// resets to the top, and there's no selection only when the list is empty
+fn clamp_selection(selected: Option<usize>, len: usize) -> Option<usize> {
+    match selected {
+        _ if len == 0 => None,
+        Some(selected) if selected < len => Some(selected),
+        _ => Some(0),
+    }
+}
+
+/// Keybindings for a screen, as (keys, description) pairs
+fn help_entries(state: &AppState) -> Vec<(&'static str, &'static str)> {
+    match state {
+        AppState::Loading => vec![],
+        AppState::RouteSelection => vec![
+            ("↑↓/jk", "Move selection"),
+            ("Enter", "List the line's stops"),
+            ("Esc/Shift-Tab", "Back to all stops"),
+        ],
+        AppState::Selection => vec![
+            ("type", "Search stops, or type a line like Q"),
+            ("Backspace", "Edit search"),
+            ("Esc", "Toggle navigation mode"),
+            ("↑↓", "Move selection"),
+            ("PgUp/PgDn", "Move selection by a page"),
+            ("Home/End", "Jump to first/last stop"),
+            ("j/k", "Move selection (navigation mode)"),
+            ("/", "Back to search (navigation mode)"),
+            ("f", "Toggle favorite (navigation mode)"),
+            ("p", "List stations or single platforms (navigation mode)"),
+            ("Tab", "Show favorites only"),
+            ("Shift-Tab", "Pick a line first / back to all stops"),
+            ("Enter", "Monitor selected stop"),
+            ("wheel", "Move selection"),
+            ("click", "Select stop, click again to monitor"),
+        ],
+        AppState::Polling { .. } => vec![
+            ("s", "Switch stop"),
+            ("r", "Refresh now"),
+            ("f", "Toggle favorite"),
+            ("o", "Cycle route filter"),
+            ("y", "Copy stop ID to clipboard"),
+            ("e", "Export a JSON snapshot for bug reports"),
+            ("d", "Toggle arrivals/departures"),
+            ("n", "Hide/show routes with no service"),
+            ("g", "Toggle countdown gauges"),
+            ("w", "Toggle clock times beside countdowns"),
+            ("a", "Cycle trains shown per route"),
+            ("c", "Toggle line color legend"),
+            ("t", "Toggle soonest-train trends"),
+            ("+/-", "Poll faster/slower"),
+            ("[/]", "Decrease/increase walk time"),
+            ("l", "Show log"),
+        ],
+        AppState::Log => vec![
+            ("l/Esc", "Close log"),
+            ("↑↓", "Scroll one line"),
+            ("PgUp/PgDn", "Scroll one page"),
+            ("d", "Toggle DEBUG logging"),
+        ],
+    }
+}
+
+fn render_help(f: &mut Frame, app: &App) {
+    let mut entries = help_entries(&app.state);
+    entries.push(("?/Esc", "Close help"));
+    entries.push(("q", "Quit (except while typing a search)"));
+    entries.push(("Ctrl-C", "Quit"));
+
+    let key_width = entries
+        .iter()
+        .map(|(keys, _)| keys.chars().count())
+        .max()
+        .unwrap_or(0);
+    let lines: Vec<Line> = entries
+        .iter()
+        .map(|(keys, description)| {
+            Line::from(format!(
+                "{:>width$}  {}",
+                keys,
+                description,
+                width = key_width
+            ))
+        })
+        .collect();
+
+    // Fit the popup to its content, plus borders
+    let height = lines.len() as u16 + 2;
+    let area = center(f.area(), Constraint::Length(50), Constraint::Length(height));
+
+    let help = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title("Help")
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+        .wrap(Wrap { trim: false });
+
+    // Clear whatever is underneath so the popup is readable
+    f.render_widget(Clear, area);
+    f.render_widget(help, area);
 }
 
-async fn run_app() -> Result<()> {
-    logger::initialize_logging()?;
+/// Builds the TrainChecker, loading the cached GTFS without network access when offline.
+/// `progress` is called as the schedule downloads.
+async fn create_checker(
+    offline: bool,
+    config: &TrainCheckerConfig,
+    progress: &(dyn Fn(DownloadProgress) + Sync),
+) -> Result<TrainChecker> {
+    let cache_path = storage::gtfs_cache_path();
+    if offline {
+        TrainChecker::new_offline(&cache_path)
+    } else {
+        TrainChecker::new_with_cache_and_progress(&cache_path, config, progress).await
+    }
+}
+
+async fn run_app(
+    offline: bool,
+    config: TrainCheckerConfig,
+    initial_stop: Option<String>,
+    station_view: bool,
+) -> Result<()> {
     info!("Starting train checker application");
 
     let terminal = ratatui::init();
+    ratatui::crossterm::execute!(std::io::stdout(), EnableMouseCapture)?;
 
-    let app_result = App::new().run(terminal).await;
+    let mut app = App::new(offline, config, initial_stop);
+    app.station_view = station_view;
+    let app_result = app.run(terminal).await;
+    // Best effort, the terminal is being restored either way
+    let _ = ratatui::crossterm::execute!(std::io::stdout(), DisableMouseCapture);
     ratatui::restore();
     app_result
 }
 
+/// Prints a single JSON snapshot of a stop's status to stdout
+async fn run_json(stop_id: &str, offline: bool, config: &TrainCheckerConfig) -> Result<()> {
+    info!("Fetching JSON status for stop {}", stop_id);
+
+    let checker = create_checker(offline, config, &|_| {}).await?;
+    let status = checker.get_stop_status(stop_id).await?;
+    println!("{}", serde_json::to_string_pretty(&status)?);
+    Ok(())
+}
+
+/// Prints a plain-text arrival board for a stop every `interval` until Ctrl-C
+async fn run_watch(
+    stop_id: &str,
+    interval: Duration,
+    offline: bool,
+    config: TrainCheckerConfig,
+) -> Result<()> {
+    info!("Watching stop {} every {}s", stop_id, interval.as_secs());
+
+    let checker = create_checker(offline, &config, &|_| {}).await?;
+    if !checker.is_valid_stop(stop_id) {
+        return Err(anyhow::anyhow!("Invalid stop ID: {}", stop_id));
+    }
+    // Watch mode can run for days, so pick up schedule changes without a restart
+    let config = TrainCheckerConfig {
+        update_interval: interval,
+        gtfs_refresh_interval: Some(GTFS_REFRESH_INTERVAL),
+        ..config
+    };
+    let monitor =
+        StopMonitor::with_checker(checker, config).with_gtfs_cache(storage::gtfs_cache_path());
+
+    #[cfg(feature = "systemd")]
+    let mut notifier = systemd::Notifier::from_env().unwrap_or_else(|e| {
+        tracing::warn!("Not notifying systemd: {}", e);
+        None
+    });
+    let on_status = move |status: StopStatus| {
+        println!("{}", format_board(&status));
+        #[cfg(feature = "systemd")]
+        if let Some(notifier) = &mut notifier {
+            notifier.poll_succeeded();
+        }
+    };
+
+    tokio::select! {
+        result = monitor.monitor_stop(stop_id, on_status) => result,
+        _ = tokio::signal::ctrl_c() => {
+            info!("Stopped watching stop {}", stop_id);
+            Ok(())
+        }
+    }
+}
+
+/// Formats a stop's arrivals as a plain-text board, one route per line
+fn format_board(status: &StopStatus) -> String {
+    let now = chrono::Utc::now();
+    let mut heading = status
+        .stop_name
+        .clone()
+        .unwrap_or_else(|| status.stop_id.clone());
+    if let Some(direction) = status.direction {
+        heading.push_str(&format!(" — {}", direction.label()));
+    }
+    if status.simulated {
+        heading.push_str(" [SIMULATED]");
+    }
+    if status.is_partial() {
+        heading.push_str(&format!(
+            " [{}/{} feeds]",
+            status.feeds_succeeded, status.feeds_total
+        ));
+    }
+    let local_now = now.with_timezone(&chrono_tz::America::New_York);
+    let mut lines = vec![format!("{} ({})", heading, local_now.format("%H:%M:%S"))];
+
+    if status.train_arrivals.is_empty() {
+        lines.push("  No upcoming trains found".to_string());
+    }
+    // A row per destination, since some routes split to different terminals
+    let rows: Vec<(String, String)> = status
+        .arrivals_by_destination()
+        .into_iter()
+        .map(|((route_id, headsign), arrivals)| {
+            let mut label = route_label(route_id, arrivals.first().copied());
+            if let Some(headsign) = headsign {
+                label.push_str(&format!(" → {}", headsign));
+            }
+            let times: Vec<String> = arrivals
+                .iter()
+                .map(|arrival| format_arrival_countdown(arrival, arrival.seconds_until(now)))
+                .collect();
+            (label, times.join(", "))
+        })
+        .collect();
+    let label_width = rows
+        .iter()
+        .map(|(label, _)| label.chars().count() + 2)
+        .max()
+        .unwrap_or(0)
+        .max(12);
+    for (label, times) in rows {
+        lines.push(format!("  {:<width$}{}", label, times, width = label_width));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Prints all stops as tab-separated ID and name lines
+async fn run_list_stops(parents_only: bool, offline: bool) -> Result<()> {
+    info!("Listing stops (parents only: {})", parents_only);
+
+    let checker = create_checker(offline, &TrainCheckerConfig::default(), &|_| {}).await?;
+    let stops = if parents_only {
+        checker.get_parent_stations()
+    } else {
+        checker.get_all_stops()
+    };
+    for (stop_id, name) in stops {
+        println!("{}\t{}", stop_id, name.unwrap_or_default());
+    }
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    run_app().await
+    let cli = Cli::parse();
+    logger::initialize_logging_in(cli.data_dir)?;
+
+    if cli.list_stops {
+        return run_list_stops(cli.parents_only, cli.offline).await;
+    }
+
+    let config = TrainCheckerConfig {
+        api_key: cli.api_key,
+        ..TrainCheckerConfig::default()
+    };
+    let interval = cli.interval.map(Duration::from_secs);
+
+    match cli.stop {
+        Some(stop_id) if cli.json => run_json(&stop_id, cli.offline, &config).await,
+        Some(stop_id) if cli.watch => {
+            let interval = interval.unwrap_or(DEFAULT_WATCH_INTERVAL);
+            run_watch(&stop_id, interval, cli.offline, config).await
+        }
+        initial_stop => {
+            let config = match interval {
+                Some(interval) => tui_config(config, interval).unwrap_or_else(|message| {
+                    Cli::command()
+                        .error(ErrorKind::ValueValidation, message)
+                        .exit()
+                }),
+                None => config,
+            };
+            run_app(cli.offline, config, initial_stop, cli.stations).await
+        }
+    }
+}
+
+/// Applies an --interval to the TUI's config. The TUI's interval can only be adjusted
+/// within the config's bounds, so one outside them is rejected.
+fn tui_config(
+    config: TrainCheckerConfig,
+    interval: Duration,
+) -> Result<TrainCheckerConfig, String> {
+    if interval < config.min_interval || interval > config.max_interval {
+        return Err(format!(
+            "--interval must be between {} and {} seconds in the TUI",
+            config.min_interval.as_secs(),
+            config.max_interval.as_secs()
+        ));
+    }
+    Ok(TrainCheckerConfig {
+        update_interval: interval,
+        ..config
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stops() -> Vec<(String, String)> {
+        [
+            ("R16N", "Times Sq-42 St (R16N)"),
+            ("R16S", "Times Sq-42 St (R16S)"),
+            ("G22N", "Court Sq (G22N)"),
+            ("A27S", "42 St-Port Authority Bus Terminal (A27S)"),
+        ]
+        .into_iter()
+        .map(|(id, name)| (id.to_string(), name.to_string()))
+        .collect()
+    }
+
+    #[test]
+    fn selectable_stops_are_platforms_or_childless_stations() {
+        // Excerpt of the MTA's stops.txt, plus a station shipped without platforms
+        let fixture = [
+            ("101", "Van Cortlandt Park-242 St"),
+            ("101N", "Van Cortlandt Park-242 St"),
+            ("101S", "Van Cortlandt Park-242 St"),
+            ("A27", "42 St-Port Authority Bus Terminal"),
+            ("A27N", "42 St-Port Authority Bus Terminal"),
+            ("A27S", "42 St-Port Authority Bus Terminal"),
+            ("S01", "Franklin Av"),
+            ("S01N", "Franklin Av"),
+            ("S01S", "Franklin Av"),
+            ("H19", "Broad Channel"),
+            ("H19S", "Broad Channel"),
+            ("X01", "New Station"),
+        ];
+        let stops = fixture
+            .into_iter()
+            .map(|(id, name)| (id.to_string(), Some(name.to_string())))
+            .collect();
+
+        let ids: Vec<String> = selectable_stops(stops)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+
+        assert_eq!(
+            ids,
+            vec![
+                "101N", "101S", "A27N", "A27S", "S01N", "S01S", "H19S", "X01"
+            ]
+        );
+    }
+
+    #[test]
+    fn gauge_fills_as_the_train_approaches() {
+        assert_eq!(gauge_ratio(GAUGE_HORIZON.as_secs() as i64 + 60), 0.0);
+        assert_eq!(gauge_ratio(GAUGE_HORIZON.as_secs() as i64 / 2), 0.5);
+        assert_eq!(gauge_ratio(0), 1.0);
+        assert_eq!(gauge_ratio(-30), 1.0);
+    }
+
+    #[test]
+    fn empty_boards_tell_no_trains_from_no_data() {
+        let status = |feeds_total, feeds_succeeded| StopStatus {
+            feeds_total,
+            feeds_succeeded,
+            ..status_with_soonest("Q", None)
+        };
+        let message = |status: Option<&StopStatus>, poll_failed| {
+            empty_board_message(status, poll_failed, None).0
+        };
+
+        assert_eq!(message(None, false), "Loading train data...");
+        assert_eq!(message(None, true), "Realtime data unavailable — retrying");
+        assert_eq!(
+            message(Some(&status(1, 1)), false),
+            "No upcoming trains — live data is up to date"
+        );
+        assert_eq!(
+            message(Some(&status(2, 1)), false),
+            "No upcoming trains — live data is up to date (1 of 2 feeds unavailable)"
+        );
+        assert_eq!(
+            message(Some(&status(1, 1)), true),
+            "Realtime data unavailable — retrying"
+        );
+        let simulated = StopStatus {
+            simulated: true,
+            ..status(0, 0)
+        };
+        assert_eq!(
+            message(Some(&simulated), false),
+            "No simulated trains — offline, live data isn't fetched"
+        );
+    }
+
+    #[test]
+    fn clock_times_read_like_station_clocks() {
+        use chrono::TimeZone;
+        let at = |hour, minute| {
+            chrono_tz::America::New_York
+                .with_ymd_and_hms(2024, 5, 1, hour, minute, 0)
+                .unwrap()
+        };
+        assert_eq!(format_clock_time(at(15, 42)), "3:42 PM");
+        assert_eq!(format_clock_time(at(0, 5)), "12:05 AM");
+    }
+
+    #[test]
+    fn loading_subtitle_shows_download_progress() {
+        let progress = |downloaded, total| DownloadProgress { downloaded, total };
+        assert_eq!(
+            loading_subtitle(false, None),
+            "NYC Train Checker - Fetching GTFS data..."
+        );
+        assert_eq!(
+            loading_subtitle(false, Some(progress(3 << 20, Some(12 << 20)))),
+            "NYC Train Checker - Fetching GTFS data... 25% (3.0 of 12.0 MB)"
+        );
+        assert_eq!(
+            loading_subtitle(false, Some(progress(5 << 19, None))),
+            "NYC Train Checker - Fetching GTFS data... 2.5 MB"
+        );
+    }
+
+    #[test]
+    fn tui_interval_must_be_within_the_adjustable_range() {
+        let secs = Duration::from_secs;
+        let config = tui_config(TrainCheckerConfig::default(), secs(30)).unwrap();
+        assert_eq!(config.update_interval, secs(30));
+
+        let error = tui_config(TrainCheckerConfig::default(), secs(1)).unwrap_err();
+        assert_eq!(
+            error,
+            "--interval must be between 5 and 120 seconds in the TUI"
+        );
+        assert!(tui_config(TrainCheckerConfig::default(), secs(600)).is_err());
+    }
+
+    #[test]
+    fn adjust_interval_steps_within_bounds() {
+        let secs = Duration::from_secs;
+        let (min, max) = (secs(5), secs(120));
+
+        assert_eq!(adjust_interval(secs(10), -5, min, max), secs(5));
+        assert_eq!(adjust_interval(secs(10), 5, min, max), secs(15));
+        // At the bounds the interval stays put
+        assert_eq!(adjust_interval(secs(5), -5, min, max), secs(5));
+        assert_eq!(adjust_interval(secs(120), 5, min, max), secs(120));
+        // Steps that would overshoot stop at the bound
+        assert_eq!(adjust_interval(secs(7), -5, min, max), secs(5));
+        assert_eq!(adjust_interval(secs(118), 5, min, max), secs(120));
+        assert_eq!(adjust_interval(secs(3), -5, min, max), secs(5));
+    }
+
+    #[test]
+    fn adjust_interval_pulls_out_of_range_values_back_in() {
+        let secs = Duration::from_secs;
+        let (min, max) = (secs(5), secs(120));
+
+        assert_eq!(adjust_interval(secs(1), 5, min, max), secs(6));
+        assert_eq!(adjust_interval(secs(300), -5, min, max), secs(120));
+        assert_eq!(adjust_interval(secs(10), 0, secs(30), secs(20)), secs(30));
+    }
+
+    #[test]
+    fn empty_query_matches_every_stop() {
+        assert_eq!(filter_stops_impl(&stops(), ""), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn query_matches_name_or_id_ignoring_case() {
+        assert_eq!(filter_stops_impl(&stops(), "42 st"), vec![0, 1, 3]);
+        assert_eq!(filter_stops_impl(&stops(), "g22"), vec![2]);
+    }
+
+    #[test]
+    fn route_id_query_lists_the_routes_stops() {
+        let route = |id: &str| RouteInfo {
+            route_id: id.to_string(),
+            short_name: Some(id.to_string()),
+            long_name: None,
+            color: (0, 0, 0),
+            text_color: (255, 255, 255),
+        };
+        let routes = vec![route("Q"), route("6X"), route("G")];
+        let stop_routes = HashMap::from([
+            ("R16N".to_string(), HashSet::from(["Q".to_string()])),
+            ("R16S".to_string(), HashSet::from(["Q".to_string()])),
+            ("G22N".to_string(), HashSet::from(["G".to_string()])),
+        ]);
+
+        assert_eq!(route_query("q", &routes), Some("Q"));
+        assert_eq!(route_query(" 6x ", &routes), Some("6X"));
+        assert_eq!(route_query("Court Sq", &routes), None);
+        assert_eq!(route_query("R", &routes), None);
+        assert_eq!(route_query("", &routes), None);
+        assert_eq!(
+            filter_stops_by_route(&stops(), &stop_routes, "Q"),
+            vec![0, 1]
+        );
+        assert!(filter_stops_by_route(&stops(), &stop_routes, "6X").is_empty());
+    }
+
+    #[test]
+    fn query_without_matches_is_empty() {
+        assert!(filter_stops_impl(&stops(), "Coney Island").is_empty());
+    }
+
+    #[test]
+    fn normalize_expands_ordinals_and_abbreviations() {
+        assert_eq!(normalize_stop_query("42nd Street"), "42 st");
+        assert_eq!(
+            normalize_stop_query("42 St-Port Authority"),
+            "42 st port authority"
+        );
+        assert_eq!(normalize_stop_query("Court Square"), "court sq");
+        assert_eq!(normalize_stop_query("5th Avenue"), "5 av");
+        assert_eq!(normalize_stop_query("Atlantic Ave"), "atlantic av");
+        assert_eq!(normalize_stop_query("1st Av"), "1 av");
+        assert_eq!(normalize_stop_query("3rd"), "3");
+        // Words that only look like ordinals are left alone
+        assert_eq!(
+            normalize_stop_query("Sutphin Blvd-Archer Av"),
+            "sutphin blvd archer av"
+        );
+        assert_eq!(normalize_stop_query("East 180 St"), "east 180 st");
+    }
+
+    #[test]
+    fn query_matches_spelled_out_names() {
+        assert_eq!(filter_stops_impl(&stops(), "42nd street"), vec![0, 1, 3]);
+        assert_eq!(filter_stops_impl(&stops(), "Court Square"), vec![2]);
+    }
+
+    fn status_with_soonest(route_id: &str, seconds: Option<i32>) -> StopStatus {
+        let arrival_local = chrono::Utc::now().with_timezone(&chrono_tz::America::New_York);
+        let arrivals = seconds.map(|seconds| TrainArrival {
+            route_id: route_id.to_string(),
+            route_name: None,
+            arrival_time: seconds,
+            human_time: String::new(),
+            arrival_local,
+            departure_time: None,
+            departure_local: None,
+            trip_id: None,
+            is_express: false,
+            origin_time: None,
+            headsign: None,
+            approximate: false,
+        });
+        StopStatus {
+            stop_id: "R16N".to_string(),
+            stop_name: None,
+            direction: None,
+            routes: HashSet::from([route_id.to_string()]),
+            train_arrivals: arrivals
+                .map(|arrival| (route_id.to_string(), vec![arrival]))
+                .into_iter()
+                .collect(),
+            simulated: false,
+            feeds_total: 1,
+            feeds_succeeded: 1,
+        }
+    }
+
+    #[test]
+    fn status_history_is_capped_and_tracks_the_soonest_train() {
+        let mut app = App::new(true, TrainCheckerConfig::default(), None);
+        for seconds in [300, 240, 180] {
+            app.record_status(vec![status_with_soonest("Q", Some(seconds))]);
+        }
+        app.record_status(vec![status_with_soonest("Q", None)]);
+        app.record_status(vec![status_with_soonest("Q", Some(-20))]);
+
+        assert_eq!(
+            soonest_arrival_history(&app.status_history, "R16N", "Q"),
+            vec![Some(300), Some(240), Some(180), None, Some(0)]
+        );
+
+        for _ in 0..STATUS_HISTORY_LEN {
+            app.record_status(vec![status_with_soonest("Q", Some(60))]);
+        }
+        assert_eq!(app.status_history.len(), STATUS_HISTORY_LEN);
+        assert!(
+            soonest_arrival_history(&app.status_history, "R16N", "Q")
+                .iter()
+                .all(|seconds| *seconds == Some(60))
+        );
+    }
+
+    #[tokio::test]
+    async fn results_of_a_poll_for_the_previous_stop_are_discarded() {
+        let mut app = App::new(true, TrainCheckerConfig::default(), None);
+        app.train_checker = Some(Arc::new(
+            TrainChecker::from_gtfs_path(
+                &PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/gtfs"),
+            )
+            .unwrap(),
+        ));
+        let (tx, _rx) = mpsc::unbounded_channel();
+
+        app.state = AppState::Polling {
+            stop_id: "R16N".to_string(),
+            stop_name: "Times Sq-42 St".to_string(),
+        };
+        app.spawn_poll(&tx);
+        let stale_poll = app.poll_id;
+        // Switching stops abandons the poll, and the next one can start right away. Done
+        // by hand since start_polling also saves the stop for the next run.
+        app.cancel_poll();
+        app.state = AppState::Polling {
+            stop_id: "R16S".to_string(),
+            stop_name: "Times Sq-42 St".to_string(),
+        };
+        assert!(app.poll_cancel.is_none() && !app.refreshing);
+        app.spawn_poll(&tx);
+
+        app.handle_app_event(AppEvent::StopStatusUpdate(
+            stale_poll,
+            status_with_soonest("Q", Some(60)),
+        ));
+        app.handle_app_event(AppEvent::StopStatusError(stale_poll, "timeout".to_string()));
+        assert!(app.current_stop_status.is_none());
+        assert!(app.last_poll_error.is_none());
+        assert!(app.refreshing);
+
+        let status = StopStatus {
+            stop_id: "R16S".to_string(),
+            ..status_with_soonest("R", Some(60))
+        };
+        app.handle_app_event(AppEvent::StopStatusUpdate(app.poll_id, status));
+        assert_eq!(app.current_stop_status.unwrap().stop_id, "R16S");
+        assert!(!app.refreshing);
+    }
+
+    #[test]
+    fn stops_without_live_data_stop_polling() {
+        let mut app = App::new(true, TrainCheckerConfig::default(), None);
+        app.state = AppState::Polling {
+            stop_id: "X01N".to_string(),
+            stop_name: "New Station".to_string(),
+        };
+        assert!(app.should_poll());
+        app.poll_id = 1;
+        app.refreshing = true;
+
+        app.handle_app_event(AppEvent::NoLiveData(1, "no feed".to_string()));
+
+        assert_eq!(app.no_live_data.as_deref(), Some("no feed"));
+        assert_eq!(app.poll_failures, 0);
+        assert!(!app.should_poll());
+    }
+
+    #[test]
+    fn station_polls_fill_a_board_per_platform() {
+        let mut app = App::new(true, TrainCheckerConfig::default(), None);
+        app.state = AppState::Polling {
+            stop_id: "R16".to_string(),
+            stop_name: "Times Sq-42 St".to_string(),
+        };
+        app.poll_id = 1;
+        app.refreshing = true;
+
+        let station = StationStatus {
+            stop_id: "R16".to_string(),
+            stop_name: Some("Times Sq-42 St".to_string()),
+            platforms: vec![
+                status_with_soonest("Q", Some(60)),
+                StopStatus {
+                    stop_id: "R16S".to_string(),
+                    ..status_with_soonest("R", Some(120))
+                },
+            ],
+        };
+        app.handle_app_event(AppEvent::StationStatusUpdate(1, station));
+
+        assert_eq!(
+            app.current_station_status.as_ref().unwrap().platforms.len(),
+            2
+        );
+        assert!(app.current_stop_status.is_none());
+        assert_eq!(app.served_routes(), HashSet::from(["Q", "R"]));
+    }
+
+    #[test]
+    fn station_polls_feed_the_trends_and_snapshots() {
+        let mut app = App::new(true, TrainCheckerConfig::default(), None);
+        app.train_checker = Some(Arc::new(
+            TrainChecker::from_gtfs_path(
+                &PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/gtfs"),
+            )
+            .unwrap(),
+        ));
+        app.state = AppState::Polling {
+            stop_id: "R16".to_string(),
+            stop_name: "Times Sq-42 St".to_string(),
+        };
+        app.poll_id = 1;
+        app.refreshing = true;
+
+        let station = StationStatus {
+            stop_id: "R16".to_string(),
+            stop_name: Some("Times Sq-42 St".to_string()),
+            platforms: vec![
+                status_with_soonest("Q", Some(60)),
+                StopStatus {
+                    stop_id: "R16S".to_string(),
+                    ..status_with_soonest("R", Some(120))
+                },
+            ],
+        };
+        app.handle_app_event(AppEvent::StationStatusUpdate(1, station));
+
+        assert_eq!(
+            soonest_arrival_history(&app.status_history, "R16N", "Q"),
+            vec![Some(60)]
+        );
+        assert_eq!(
+            soonest_arrival_history(&app.status_history, "R16S", "R"),
+            vec![Some(120)]
+        );
+
+        let (stop_id, snapshot) = app.snapshot().unwrap();
+        assert_eq!(stop_id, "R16");
+        assert!(snapshot.status.is_none());
+        assert_eq!(snapshot.station.unwrap().platforms.len(), 2);
+    }
+
+    #[test]
+    fn selection_is_kept_while_in_range() {
+        assert_eq!(clamp_selection(Some(2), 3), Some(2));
+    }
+
+    #[test]
+    fn selection_resets_to_top_when_results_shrink() {
+        assert_eq!(clamp_selection(Some(3), 2), Some(0));
+        assert_eq!(clamp_selection(None, 2), Some(0));
+    }
+
+    #[test]
+    fn selection_is_cleared_without_results() {
+        assert_eq!(clamp_selection(Some(1), 0), None);
+        assert_eq!(clamp_selection(None, 0), None);
+    }
 }
@@ -0,0 +1,136 @@
+use std::fmt::Write;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use crate::TrainChecker;
+
+// Upper bounds of the feed latency histogram buckets, in seconds
+const LATENCY_BUCKETS: [f64; 8] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Feed latency observations bucketed for a Prometheus histogram
+#[derive(Debug, Default)]
+pub(crate) struct LatencyHistogram {
+    bucket_counts: [u64; LATENCY_BUCKETS.len()], // non-cumulative count per bucket
+    count: u64,
+    sum: f64, // seconds
+}
+
+impl LatencyHistogram {
+    pub(crate) fn observe(&mut self, latency: Duration) {
+        let seconds = latency.as_secs_f64();
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|&le| seconds <= le) {
+            self.bucket_counts[bucket] += 1;
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+impl TrainChecker {
+    /// Formats request counters and the feed latency histogram in the Prometheus text
+    /// exposition format, ready to be served at `/metrics`
+    pub fn prometheus_metrics(&self) -> String {
+        let mut out = String::new();
+        self.write_prometheus_metrics(&mut out)
+            .expect("writing to a String cannot fail");
+        out
+    }
+
+    fn write_prometheus_metrics(&self, out: &mut String) -> std::fmt::Result {
+        let total_requests = self.total_requests.load(Ordering::Relaxed);
+        writeln!(
+            out,
+            "# HELP train_checker_requests_total Realtime feed requests made."
+        )?;
+        writeln!(out, "# TYPE train_checker_requests_total counter")?;
+        writeln!(out, "train_checker_requests_total {}", total_requests)?;
+
+        writeln!(
+            out,
+            "# HELP train_checker_failed_requests_total Realtime feed requests that failed."
+        )?;
+        writeln!(out, "# TYPE train_checker_failed_requests_total counter")?;
+        writeln!(
+            out,
+            "train_checker_failed_requests_total {}",
+            self.get_failed_requests_count()
+        )?;
+
+        // Per-feed breakdown, sorted so the output is stable between scrapes
+        let mut feed_health: Vec<_> = self.get_feed_health().into_iter().collect();
+        feed_health.sort_by(|a, b| a.0.cmp(&b.0));
+        writeln!(
+            out,
+            "# HELP train_checker_feed_requests_total Realtime feed requests by feed and outcome."
+        )?;
+        writeln!(out, "# TYPE train_checker_feed_requests_total counter")?;
+        for (feed, health) in &feed_health {
+            writeln!(
+                out,
+                "train_checker_feed_requests_total{{feed=\"{}\",outcome=\"success\"}} {}",
+                feed, health.successes
+            )?;
+            writeln!(
+                out,
+                "train_checker_feed_requests_total{{feed=\"{}\",outcome=\"failure\"}} {}",
+                feed, health.failures
+            )?;
+            writeln!(
+                out,
+                "train_checker_feed_requests_total{{feed=\"{}\",outcome=\"throttled\"}} {}",
+                feed, health.throttled
+            )?;
+        }
+
+        let stats = self.request_stats.lock().unwrap();
+
+        if let Some(last_poll) = stats.last_successful_poll {
+            writeln!(
+                out,
+                "# HELP train_checker_last_successful_poll_timestamp_seconds Unix time of the last poll with at least one feed."
+            )?;
+            writeln!(
+                out,
+                "# TYPE train_checker_last_successful_poll_timestamp_seconds gauge"
+            )?;
+            writeln!(
+                out,
+                "train_checker_last_successful_poll_timestamp_seconds {}",
+                last_poll.timestamp()
+            )?;
+        }
+
+        let histogram = &stats.latency_histogram;
+        writeln!(
+            out,
+            "# HELP train_checker_feed_latency_seconds Time taken to fetch a realtime feed."
+        )?;
+        writeln!(out, "# TYPE train_checker_feed_latency_seconds histogram")?;
+        let mut cumulative = 0;
+        for (le, bucket_count) in LATENCY_BUCKETS.iter().zip(histogram.bucket_counts) {
+            cumulative += bucket_count;
+            writeln!(
+                out,
+                "train_checker_feed_latency_seconds_bucket{{le=\"{}\"}} {}",
+                le, cumulative
+            )?;
+        }
+        writeln!(
+            out,
+            "train_checker_feed_latency_seconds_bucket{{le=\"+Inf\"}} {}",
+            histogram.count
+        )?;
+        writeln!(
+            out,
+            "train_checker_feed_latency_seconds_sum {}",
+            histogram.sum
+        )?;
+        writeln!(
+            out,
+            "train_checker_feed_latency_seconds_count {}",
+            histogram.count
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::logger;
+
+const LAST_STOP_FILE: &str = "last_stop";
+const FAVORITES_FILE: &str = "favorites.json";
+const GTFS_CACHE_FILE: &str = "gtfs_subway.zip";
+
+fn last_stop_path() -> PathBuf {
+    logger::get_data_dir().join(LAST_STOP_FILE)
+}
+
+fn favorites_path() -> PathBuf {
+    logger::get_data_dir().join(FAVORITES_FILE)
+}
+
+/// Location of the cached static GTFS zip, used by offline mode
+pub fn gtfs_cache_path() -> PathBuf {
+    logger::get_data_dir().join(GTFS_CACHE_FILE)
+}
+
+/// Reads the stop ID that was last polled, if one was saved
+pub fn load_last_stop() -> Option<String> {
+    let contents = std::fs::read_to_string(last_stop_path()).ok()?;
+    let stop_id = contents.trim();
+    if stop_id.is_empty() {
+        None
+    } else {
+        Some(stop_id.to_string())
+    }
+}
+
+/// Saves the stop ID being polled so it can be restored on the next run
+pub fn save_last_stop(stop_id: &str) -> Result<()> {
+    std::fs::create_dir_all(logger::get_data_dir())?;
+    std::fs::write(last_stop_path(), stop_id)?;
+    Ok(())
+}
+
+/// Reads the set of bookmarked stop IDs, returning an empty set if none were saved
+pub fn load_favorites() -> BTreeSet<String> {
+    std::fs::read_to_string(favorites_path())
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Saves the set of bookmarked stop IDs
+pub fn save_favorites(favorites: &BTreeSet<String>) -> Result<()> {
+    std::fs::create_dir_all(logger::get_data_dir())?;
+    std::fs::write(favorites_path(), serde_json::to_string_pretty(favorites)?)?;
+    Ok(())
+}
+
+/// Writes a snapshot as pretty JSON to a timestamped file in the data directory, returning
+/// where it was written
+pub fn save_snapshot(
+    stop_id: &str,
+    taken_at: DateTime<Utc>,
+    snapshot: &impl Serialize,
+) -> Result<PathBuf> {
+    let file_name = format!(
+        "snapshot_{}_{}.json",
+        stop_id,
+        taken_at.format("%Y%m%d_%H%M%S")
+    );
+    let path = logger::get_data_dir().join(file_name);
+    std::fs::create_dir_all(logger::get_data_dir())?;
+    std::fs::write(&path, serde_json::to_string_pretty(snapshot)?)?;
+    Ok(path)
+}
@@ -1,2 +1,3 @@
 // Generated protobuf code for NYC MTA Subway extensions
-include!(concat!(env!("OUT_DIR"), "/transit_realtime.rs"));
\ No newline at end of file
+#![allow(dead_code, clippy::all)]
+include!(concat!(env!("OUT_DIR"), "/transit_realtime.rs"));
@@ -0,0 +1,86 @@
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::net::{SocketAddr, UnixDatagram};
+
+use anyhow::{Context, Result};
+use tracing::warn;
+
+/// Reports to systemd over its notify protocol, so `--watch` can run as a `Type=notify`
+/// service with `WatchdogSec=` set
+pub struct Notifier {
+    socket: UnixDatagram,
+    address: SocketAddr, // from NOTIFY_SOCKET
+    ready: bool,         // READY=1 has been sent
+}
+
+impl Notifier {
+    /// Opens the socket systemd passed in `NOTIFY_SOCKET`, or None when not run by systemd
+    pub fn from_env() -> Result<Option<Self>> {
+        match std::env::var_os("NOTIFY_SOCKET") {
+            Some(path) => Self::new(&path).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn new(path: &OsStr) -> Result<Self> {
+        Ok(Self {
+            socket: UnixDatagram::unbound().context("Failed to create notify socket")?,
+            address: socket_address(path)?,
+            ready: false,
+        })
+    }
+
+    /// Reports a successful poll: READY=1 the first time, then WATCHDOG=1 to show the
+    /// monitor is still alive. Failures are logged, since they shouldn't stop the monitor.
+    pub fn poll_succeeded(&mut self) {
+        let message = if self.ready { "WATCHDOG=1" } else { "READY=1" };
+        match self.socket.send_to_addr(message.as_bytes(), &self.address) {
+            Ok(_) => self.ready = true,
+            Err(e) => warn!("Failed to notify systemd ({}): {}", message, e),
+        }
+    }
+}
+
+/// Parses `NOTIFY_SOCKET`, a filesystem path or, with a leading '@', an abstract socket name
+fn socket_address(path: &OsStr) -> Result<SocketAddr> {
+    if let Some(name) = path.as_bytes().strip_prefix(b"@") {
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::linux::net::SocketAddrExt;
+            return SocketAddr::from_abstract_name(name)
+                .context("Invalid abstract socket in NOTIFY_SOCKET");
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = name;
+            return Err(anyhow::anyhow!(
+                "Abstract sockets in NOTIFY_SOCKET are only supported on Linux"
+            ));
+        }
+    }
+    SocketAddr::from_pathname(path).context("Invalid socket path in NOTIFY_SOCKET")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ready_is_sent_once_then_watchdog_pings() {
+        let path =
+            std::env::temp_dir().join(format!("train-checker-notify-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let systemd = UnixDatagram::bind(&path).unwrap();
+
+        let mut notifier = Notifier::new(path.as_os_str()).unwrap();
+        notifier.poll_succeeded();
+        notifier.poll_succeeded();
+
+        let mut message = [0; 64];
+        let len = systemd.recv(&mut message).unwrap();
+        assert_eq!(&message[..len], b"READY=1");
+        let len = systemd.recv(&mut message).unwrap();
+        assert_eq!(&message[..len], b"WATCHDOG=1");
+        std::fs::remove_file(&path).unwrap();
+    }
+}